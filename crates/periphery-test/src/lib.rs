@@ -0,0 +1,214 @@
+//! solana-program-test harness for end-to-end tests of the Saber periphery programs.
+//!
+//! [TestEnv::start] boots a bank with the mint proxy, lockup, redeemer,
+//! add-decimals, continuation router and timelock programs loaded natively
+//! and the StableSwap program loaded from its released binary. The fixtures
+//! on [TestEnv] create mints, pools, wrappers, releases, redeemers and
+//! timelocks, and [RouteBuilder] assembles continuation router routes over them.
+#![deny(rustdoc::all)]
+#![allow(rustdoc::missing_doc_code_examples)]
+
+mod pool;
+mod proxy;
+mod queue;
+mod redeem;
+mod release;
+mod route;
+mod token;
+mod wrapper;
+
+pub use pool::*;
+pub use proxy::*;
+pub use queue::*;
+pub use redeem::*;
+pub use route::*;
+pub use wrapper::*;
+
+use std::path::PathBuf;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::{AccountDeserialize, AccountSerialize};
+use solana_program_test::{processor, ProgramTest, ProgramTestContext};
+use solana_sdk::account::Account;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::transaction::Transaction;
+use solana_sdk::transport::TransportError;
+
+/// Result of processing transactions in the [TestEnv].
+pub type Result<T> = std::result::Result<T, TransportError>;
+
+/// Directory searched for `stable_swap.so` when `BPF_OUT_DIR` is not set.
+/// Populated by `scripts/download-programs.sh`.
+pub const DEFAULT_BPF_OUT_DIR: &str =
+    concat!(env!("CARGO_MANIFEST_DIR"), "/../../artifacts/programs");
+
+/// Builds a [ProgramTest] with every periphery program and StableSwap loaded.
+///
+/// `owner` becomes the owner of the [lockup::LockupConfig], which is written
+/// into the genesis accounts since it can otherwise only be created by migrating
+/// the deprecated lockup state.
+pub fn program_test(owner: &Pubkey) -> ProgramTest {
+    let bpf_out_dir = std::env::var("BPF_OUT_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from(DEFAULT_BPF_OUT_DIR));
+    assert!(
+        bpf_out_dir.join("stable_swap.so").exists(),
+        "stable_swap.so not found in {}; run scripts/download-programs.sh",
+        bpf_out_dir.display()
+    );
+    std::env::set_var("BPF_OUT_DIR", &bpf_out_dir);
+
+    // `BPF_OUT_DIR` makes [ProgramTest] prefer BPF builds, but only StableSwap
+    // is loaded from one.
+    let mut program_test = ProgramTest::default();
+    program_test.prefer_bpf(false);
+    program_test.add_program("mint_proxy", mint_proxy::ID, processor!(mint_proxy::entry));
+    program_test.add_program("lockup", lockup::ID, processor!(lockup::entry));
+    program_test.add_program("redeemer", redeemer::ID, processor!(redeemer::entry));
+    program_test.add_program(
+        "add_decimals",
+        add_decimals::ID,
+        processor!(add_decimals::entry),
+    );
+    program_test.add_program(
+        "continuation_router",
+        continuation_router::ID,
+        processor!(continuation_router::entry),
+    );
+    program_test.add_program("timelock", timelock::ID, processor!(timelock::entry));
+    program_test.prefer_bpf(true);
+    program_test.add_program("stable_swap", stable_swap_anchor::ID, None);
+    program_test.prefer_bpf(false);
+
+    let (config, bump) = Pubkey::find_program_address(&[b"LockupConfig"], &lockup::ID);
+    program_test.add_account(
+        config,
+        program_account(
+            &lockup::LockupConfig {
+                bump,
+                owner: *owner,
+                pending_owner: Pubkey::default(),
+            },
+            &lockup::ID,
+        ),
+    );
+
+    program_test
+}
+
+/// Serializes an Anchor account, including its discriminator, into a
+/// rent-exempt [Account] owned by `program_id`.
+pub fn program_account<T: AccountSerialize>(account: &T, program_id: &Pubkey) -> Account {
+    let mut data = vec![];
+    account.try_serialize(&mut data).unwrap();
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: *program_id,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// Builds an [Instruction] from Anchor accounts and instruction data.
+pub fn instruction(
+    program_id: Pubkey,
+    accounts: impl ToAccountMetas,
+    data: impl anchor_lang::InstructionData,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// A running bank with the periphery programs loaded.
+pub struct TestEnv {
+    /// The underlying [ProgramTestContext].
+    pub context: ProgramTestContext,
+    /// Owner of the lockup and of every mint proxy created by the fixtures.
+    pub owner: Keypair,
+}
+
+impl TestEnv {
+    /// Starts a new [TestEnv].
+    pub async fn start() -> TestEnv {
+        let owner = Keypair::new();
+        let context = program_test(&owner.pubkey()).start_with_context().await;
+        TestEnv { context, owner }
+    }
+
+    /// The fee payer of every transaction.
+    pub fn payer(&self) -> Pubkey {
+        self.context.payer.pubkey()
+    }
+
+    /// Processes `instructions` in a single transaction paid for by the payer.
+    /// `signers` need not include the payer.
+    pub async fn process(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<()> {
+        let payer = &self.context.payer;
+        let mut all_signers = vec![payer];
+        all_signers.extend(signers.iter().filter(|s| s.pubkey() != payer.pubkey()));
+        let blockhash = self.context.banks_client.get_latest_blockhash().await?;
+        let tx = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+        self.context.banks_client.process_transaction(tx).await
+    }
+
+    /// Processes `instructions` signed by the [TestEnv::owner] and `signers`.
+    pub async fn process_as_owner(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<()> {
+        let owner = clone_keypair(&self.owner);
+        let mut all_signers = vec![&owner];
+        all_signers.extend(signers);
+        self.process(instructions, &all_signers).await
+    }
+
+    /// Fetches and deserializes an Anchor account.
+    pub async fn account<T: AccountDeserialize>(&mut self, address: &Pubkey) -> T {
+        let account = self
+            .context
+            .banks_client
+            .get_account(*address)
+            .await
+            .unwrap()
+            .unwrap_or_else(|| panic!("account {} not found", address));
+        T::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    /// The current unix timestamp of the bank.
+    pub async fn now(&mut self) -> i64 {
+        let clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp
+    }
+
+    /// Advances to the next slot and sets the unix timestamp of the bank.
+    ///
+    /// Moving to a new slot refreshes the blockhash, so identical transactions
+    /// may be processed again afterwards.
+    pub async fn set_timestamp(&mut self, unix_timestamp: i64) {
+        let clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        self.context.warp_to_slot(clock.slot + 1).unwrap();
+        let mut clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp = unix_timestamp;
+        self.context.set_sysvar(&clock);
+    }
+}
+
+/// Copies a [Keypair], which does not implement [Clone].
+pub fn clone_keypair(keypair: &Keypair) -> Keypair {
+    Keypair::from_bytes(&keypair.to_bytes()).unwrap()
+}
@@ -0,0 +1,155 @@
+//! StableSwap pool fixtures.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_pack::Pack;
+use solana_sdk::signature::{Keypair, Signer};
+use stable_swap_client::fees::Fees;
+
+use crate::{Result, TestEnv};
+
+/// Amplification coefficient of pools created by [TestEnv::create_pool].
+pub const DEFAULT_AMP_FACTOR: u64 = 100;
+
+/// Fees of pools created by [TestEnv::create_pool]: a 4 bps trade fee.
+pub const DEFAULT_FEES: Fees = Fees {
+    admin_trade_fee_numerator: 0,
+    admin_trade_fee_denominator: 1,
+    admin_withdraw_fee_numerator: 0,
+    admin_withdraw_fee_denominator: 1,
+    trade_fee_numerator: 4,
+    trade_fee_denominator: 10_000,
+    withdraw_fee_numerator: 0,
+    withdraw_fee_denominator: 1,
+};
+
+/// One side of a [Pool].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PoolToken {
+    /// Mint of the token.
+    pub mint: Pubkey,
+    /// Reserve of the token, owned by the swap authority.
+    pub reserve: Pubkey,
+    /// Account receiving admin fees.
+    pub admin_fees: Pubkey,
+}
+
+/// A StableSwap pool.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Pool {
+    /// The swap account.
+    pub swap: Pubkey,
+    /// The swap authority PDA.
+    pub authority: Pubkey,
+    /// Mint of the LP token.
+    pub pool_mint: Pubkey,
+    /// Token A.
+    pub token_a: PoolToken,
+    /// Token B.
+    pub token_b: PoolToken,
+}
+
+impl Pool {
+    /// Returns the (input, output) sides of a swap of `input_mint`.
+    pub fn sides(&self, input_mint: &Pubkey) -> (PoolToken, PoolToken) {
+        if *input_mint == self.token_a.mint {
+            (self.token_a, self.token_b)
+        } else {
+            assert_eq!(*input_mint, self.token_b.mint, "mint not in pool");
+            (self.token_b, self.token_a)
+        }
+    }
+}
+
+impl TestEnv {
+    /// Creates a StableSwap pool of `mint_a` and `mint_b`, seeded with
+    /// `amount_a` tokens from `source_a` and `amount_b` tokens from `source_b`,
+    /// both owned by `source_authority`.
+    ///
+    /// Both mints must have the same decimals. The initial LP tokens are sent to
+    /// a new account owned by the payer, which is returned alongside the pool.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_pool(
+        &mut self,
+        mint_a: &Pubkey,
+        source_a: &Pubkey,
+        amount_a: u64,
+        mint_b: &Pubkey,
+        source_b: &Pubkey,
+        amount_b: u64,
+        source_authority: &Keypair,
+    ) -> Result<(Pool, Pubkey)> {
+        let swap = self
+            .create_account(
+                stable_swap_client::state::SwapInfo::LEN,
+                &stable_swap_anchor::ID,
+            )
+            .await?;
+        let (authority, nonce) =
+            Pubkey::find_program_address(&[swap.pubkey().as_ref()], &stable_swap_anchor::ID);
+        let admin = self.payer();
+
+        let token_a = self
+            .create_pool_token(mint_a, source_a, amount_a, source_authority, &authority)
+            .await?;
+        let token_b = self
+            .create_pool_token(mint_b, source_b, amount_b, source_authority, &authority)
+            .await?;
+        let decimals = self.mint(mint_a).await.decimals;
+        let pool_mint = self.create_mint(&authority, None, decimals).await?;
+        let destination = self.create_token_account(&pool_mint, &admin).await?;
+
+        self.process(
+            &[stable_swap_client::instruction::initialize(
+                &spl_token::ID,
+                &swap.pubkey(),
+                &authority,
+                &admin,
+                &token_a.admin_fees,
+                &token_b.admin_fees,
+                &token_a.mint,
+                &token_a.reserve,
+                &token_b.mint,
+                &token_b.reserve,
+                &pool_mint,
+                &destination,
+                nonce,
+                DEFAULT_AMP_FACTOR,
+                DEFAULT_FEES,
+            )
+            .unwrap()],
+            &[],
+        )
+        .await?;
+
+        Ok((
+            Pool {
+                swap: swap.pubkey(),
+                authority,
+                pool_mint,
+                token_a,
+                token_b,
+            },
+            destination,
+        ))
+    }
+
+    async fn create_pool_token(
+        &mut self,
+        mint: &Pubkey,
+        source: &Pubkey,
+        amount: u64,
+        source_authority: &Keypair,
+        authority: &Pubkey,
+    ) -> Result<PoolToken> {
+        let reserve = self.create_token_account(mint, authority).await?;
+        self.transfer(source, source_authority, &reserve, amount)
+            .await?;
+        let admin = self.payer();
+        let admin_fees = self.create_token_account(mint, &admin).await?;
+        Ok(PoolToken {
+            mint: *mint,
+            reserve,
+            admin_fees,
+        })
+    }
+}
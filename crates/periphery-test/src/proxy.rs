@@ -0,0 +1,251 @@
+//! Mint proxy fixtures.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::system_program;
+use lockup_manifest::mint_proxy_config_address;
+use solana_sdk::signature::{Keypair, Signer};
+
+use crate::{instruction, Result, TestEnv};
+
+/// A mint whose authority is a mint proxy owned by the [TestEnv::owner].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MintProxy {
+    /// The token mint.
+    pub mint: Pubkey,
+    /// The [mint_proxy::MintProxyConfig].
+    pub config: Pubkey,
+    /// Mint authority of the token, derived from the config.
+    pub proxy_mint_authority: Pubkey,
+}
+
+impl MintProxy {
+    /// Address of the [mint_proxy::MinterInfo] of `minter`.
+    pub fn minter_info(&self, minter: &Pubkey) -> Pubkey {
+        let (minter_info, _) = Pubkey::find_program_address(
+            &[b"MinterInfo", self.config.as_ref(), minter.as_ref()],
+            &mint_proxy::ID,
+        );
+        minter_info
+    }
+}
+
+impl TestEnv {
+    /// Creates a mint and hands its authority to a new mint proxy.
+    pub async fn create_mint_proxy(&mut self, decimals: u8, hard_cap: u64) -> Result<MintProxy> {
+        let mint_authority = Keypair::new();
+        let mint = self
+            .create_mint(&mint_authority.pubkey(), None, decimals)
+            .await?;
+        let config = mint_proxy_config_address(&mint);
+        let (proxy_mint_authority, nonce) =
+            Pubkey::find_program_address(&[b"SaberMintProxy", config.as_ref()], &mint_proxy::ID);
+
+        let ix = instruction(
+            mint_proxy::ID,
+            mint_proxy::accounts::NewProxy {
+                mint_authority: mint_authority.pubkey(),
+                proxy_mint_authority,
+                owner: self.owner.pubkey(),
+                token_mint: mint,
+                config,
+                payer: self.payer(),
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+            },
+            mint_proxy::instruction::NewProxy { nonce, hard_cap },
+        );
+        self.process(&[ix], &[&mint_authority]).await?;
+
+        Ok(MintProxy {
+            mint,
+            config,
+            proxy_mint_authority,
+        })
+    }
+
+    /// Allows `minter` to mint up to `allowance` tokens through the proxy.
+    /// Returns the address of its [mint_proxy::MinterInfo].
+    pub async fn add_minter(
+        &mut self,
+        proxy: &MintProxy,
+        minter: &Pubkey,
+        allowance: u64,
+    ) -> Result<Pubkey> {
+        let minter_info = proxy.minter_info(minter);
+        let ix = instruction(
+            mint_proxy::ID,
+            mint_proxy::accounts::MinterAddV2 {
+                config: proxy.config,
+                auth: mint_proxy::accounts::Auth {
+                    owner: self.owner.pubkey(),
+                },
+                minter: *minter,
+                minter_info,
+                payer: self.payer(),
+                system_program: system_program::ID,
+            },
+            mint_proxy::instruction::MinterAddV2 { allowance },
+        );
+        self.process_as_owner(&[ix], &[]).await?;
+        Ok(minter_info)
+    }
+
+    /// Sets the approvers of allowance increases of the proxy, co-signed by
+    /// the current approvers in `approvals`.
+    pub async fn set_approvers(
+        &mut self,
+        proxy: &MintProxy,
+        approvers: Vec<Pubkey>,
+        threshold: u8,
+        allowance_increase_limit: u64,
+        approvals: &[&Keypair],
+    ) -> Result<()> {
+        let mut ix = instruction(
+            mint_proxy::ID,
+            mint_proxy::accounts::ConfigAuth {
+                config: proxy.config,
+                owner: self.owner.pubkey(),
+            },
+            mint_proxy::instruction::SetApprovers {
+                approvers,
+                threshold,
+                allowance_increase_limit,
+            },
+        );
+        ix.accounts.extend(
+            approvals
+                .iter()
+                .map(|approver| AccountMeta::new_readonly(approver.pubkey(), true)),
+        );
+        self.process_as_owner(&[ix], approvals).await
+    }
+
+    /// Sets the allowance of `minter` without a proposal.
+    pub async fn update_minter(
+        &mut self,
+        proxy: &MintProxy,
+        minter: &Pubkey,
+        allowance: u64,
+    ) -> Result<()> {
+        let ix = instruction(
+            mint_proxy::ID,
+            mint_proxy::accounts::MinterUpdateV2 {
+                config: proxy.config,
+                inner: mint_proxy::accounts::MinterUpdate {
+                    auth: mint_proxy::accounts::Auth {
+                        owner: self.owner.pubkey(),
+                    },
+                    minter_info: proxy.minter_info(minter),
+                },
+            },
+            mint_proxy::instruction::MinterUpdateV2 { allowance },
+        );
+        self.process_as_owner(&[ix], &[]).await
+    }
+
+    /// Pauses `minter` as the owner of the proxy.
+    pub async fn pause_minter(&mut self, proxy: &MintProxy, minter: &Pubkey) -> Result<()> {
+        let ix = instruction(
+            mint_proxy::ID,
+            mint_proxy::accounts::SetMinterPaused {
+                config: proxy.config,
+                authority: self.owner.pubkey(),
+                minter_info: proxy.minter_info(minter),
+                payer: self.payer(),
+                system_program: system_program::ID,
+            },
+            mint_proxy::instruction::PauseMinter {},
+        );
+        self.process_as_owner(&[ix], &[]).await
+    }
+
+    /// Removes `minter` from the proxy.
+    pub async fn remove_minter(&mut self, proxy: &MintProxy, minter: &Pubkey) -> Result<()> {
+        let ix = instruction(
+            mint_proxy::ID,
+            mint_proxy::accounts::MinterRemoveV2 {
+                config: proxy.config,
+                inner: mint_proxy::accounts::MinterRemove {
+                    auth: mint_proxy::accounts::Auth {
+                        owner: self.owner.pubkey(),
+                    },
+                    minter: *minter,
+                    minter_info: proxy.minter_info(minter),
+                    payer: self.payer(),
+                },
+            },
+            mint_proxy::instruction::MinterRemoveV2 {},
+        );
+        self.process_as_owner(&[ix], &[]).await
+    }
+
+    /// Proposes a new allowance for `minter`.
+    /// Returns the address of the [mint_proxy::AllowanceProposal].
+    pub async fn propose_allowance(
+        &mut self,
+        proxy: &MintProxy,
+        minter: &Pubkey,
+        allowance: u64,
+    ) -> Result<Pubkey> {
+        let minter_info = proxy.minter_info(minter);
+        let (proposal, _) = Pubkey::find_program_address(
+            &[b"AllowanceProposal", minter_info.as_ref()],
+            &mint_proxy::ID,
+        );
+        let ix = instruction(
+            mint_proxy::ID,
+            mint_proxy::accounts::ProposeAllowance {
+                config: proxy.config,
+                auth: mint_proxy::accounts::Auth {
+                    owner: self.owner.pubkey(),
+                },
+                minter_info,
+                proposal,
+                payer: self.payer(),
+                system_program: system_program::ID,
+            },
+            mint_proxy::instruction::ProposeAllowance { allowance },
+        );
+        self.process_as_owner(&[ix], &[]).await?;
+        Ok(proposal)
+    }
+
+    /// Approves an allowance proposal as `approver`.
+    pub async fn approve_allowance(
+        &mut self,
+        proxy: &MintProxy,
+        proposal: &Pubkey,
+        approver: &Keypair,
+    ) -> Result<()> {
+        let ix = instruction(
+            mint_proxy::ID,
+            mint_proxy::accounts::ApproveAllowance {
+                config: proxy.config,
+                proposal: *proposal,
+                approver: approver.pubkey(),
+            },
+            mint_proxy::instruction::ApproveAllowance {},
+        );
+        self.process(&[ix], &[approver]).await
+    }
+
+    /// Executes the allowance proposal of `minter`.
+    pub async fn execute_allowance(
+        &mut self,
+        proxy: &MintProxy,
+        proposal: &Pubkey,
+        minter: &Pubkey,
+    ) -> Result<()> {
+        let ix = instruction(
+            mint_proxy::ID,
+            mint_proxy::accounts::ExecuteAllowance {
+                config: proxy.config,
+                proposal: *proposal,
+                minter_info: proxy.minter_info(minter),
+                payer: self.payer(),
+            },
+            mint_proxy::instruction::ExecuteAllowance {},
+        );
+        self.process(&[ix], &[]).await
+    }
+}
@@ -0,0 +1,132 @@
+//! Timelock fixtures.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::system_program;
+use solana_sdk::signature::{Keypair, Signer};
+
+use crate::{instruction, Result, TestEnv};
+
+impl TestEnv {
+    /// Creates a [timelock::Timelock] administered by `admin`.
+    pub async fn create_timelock(&mut self, admin: &Pubkey, delay: i64) -> Result<Pubkey> {
+        let base = Keypair::new();
+        let (timelock, _) =
+            Pubkey::find_program_address(&[b"Timelock", base.pubkey().as_ref()], &timelock::ID);
+        let ix = instruction(
+            timelock::ID,
+            timelock::accounts::CreateTimelock {
+                base: base.pubkey(),
+                timelock,
+                payer: self.payer(),
+                system_program: system_program::ID,
+            },
+            timelock::instruction::CreateTimelock {
+                admin: *admin,
+                delay,
+            },
+        );
+        self.process(&[ix], &[&base]).await?;
+        Ok(timelock)
+    }
+
+    /// Queues `ix` on the timelock, to be executed at or after `eta`.
+    /// Returns the address of the [timelock::QueuedAction].
+    pub async fn queue_action(
+        &mut self,
+        timelock: &Pubkey,
+        admin: &Keypair,
+        ix: Instruction,
+        eta: i64,
+    ) -> Result<Pubkey> {
+        let num_actions = self
+            .account::<timelock::Timelock>(timelock)
+            .await
+            .num_actions;
+        let (action, _) = Pubkey::find_program_address(
+            &[
+                b"QueuedAction",
+                timelock.as_ref(),
+                num_actions.to_le_bytes().as_ref(),
+            ],
+            &timelock::ID,
+        );
+        let ix = instruction(
+            timelock::ID,
+            timelock::accounts::QueueAction {
+                timelock: *timelock,
+                action,
+                admin: admin.pubkey(),
+                payer: self.payer(),
+                system_program: system_program::ID,
+            },
+            timelock::instruction::QueueAction { ix: ix.into(), eta },
+        );
+        self.process(&[ix], &[admin]).await?;
+        Ok(action)
+    }
+
+    /// Cancels a queued action.
+    pub async fn cancel_action(
+        &mut self,
+        timelock: &Pubkey,
+        action: &Pubkey,
+        admin: &Keypair,
+    ) -> Result<()> {
+        let ix = instruction(
+            timelock::ID,
+            timelock::accounts::CancelAction {
+                timelock: *timelock,
+                action: *action,
+                admin: admin.pubkey(),
+            },
+            timelock::instruction::CancelAction {},
+        );
+        self.process(&[ix], &[admin]).await
+    }
+
+    /// Executes a queued action, passing the accounts of its instruction.
+    pub async fn execute_action(&mut self, timelock: &Pubkey, action: &Pubkey) -> Result<()> {
+        let queued: timelock::QueuedAction = self.account(action).await;
+        let mut ix = instruction(
+            timelock::ID,
+            timelock::accounts::ExecuteAction {
+                timelock: *timelock,
+                action: *action,
+            },
+            timelock::instruction::ExecuteAction {},
+        );
+        let queued_ix: Instruction = (&queued.instruction).into();
+        // The timelock signs through the program, not the transaction.
+        ix.accounts
+            .extend(queued_ix.accounts.into_iter().map(|meta| AccountMeta {
+                is_signer: meta.is_signer && meta.pubkey != *timelock,
+                ..meta
+            }));
+        ix.accounts
+            .push(AccountMeta::new_readonly(queued_ix.program_id, false));
+        self.process(&[ix], &[]).await
+    }
+}
+
+/// Builds [timelock::set_delay] for the timelock to execute on itself.
+pub fn set_delay_ix(timelock: &Pubkey, delay: i64) -> Instruction {
+    instruction(
+        timelock::ID,
+        timelock::accounts::TimelockAuth {
+            timelock: *timelock,
+        },
+        timelock::instruction::SetDelay { delay },
+    )
+}
+
+/// Builds [timelock::set_admin] for the timelock to execute on itself.
+pub fn set_admin_ix(timelock: &Pubkey, admin: &Pubkey) -> Instruction {
+    instruction(
+        timelock::ID,
+        timelock::accounts::TimelockAuth {
+            timelock: *timelock,
+        },
+        timelock::instruction::SetAdmin { admin: *admin },
+    )
+}
@@ -0,0 +1,250 @@
+//! Redeemer fixtures.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::system_program;
+use solana_sdk::signature::{Keypair, Signer};
+
+use crate::{instruction, MintProxy, Result, TestEnv};
+
+/// A redeemer of IOU tokens for redemption tokens.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Redeemer {
+    /// The [redeemer::Redeemer] PDA.
+    pub redeemer: Pubkey,
+    /// Mint of the IOU token.
+    pub iou_mint: Pubkey,
+    /// Mint of the redemption token.
+    pub redemption_mint: Pubkey,
+    /// Vault of the redemption token, owned by the redeemer.
+    pub redemption_vault: Pubkey,
+}
+
+impl Redeemer {
+    /// Accounts of a redemption of `iou_source` into `redemption_destination`.
+    pub fn redeem_tokens(
+        &self,
+        source_authority: &Pubkey,
+        iou_source: &Pubkey,
+        redemption_destination: &Pubkey,
+    ) -> redeemer::accounts::RedeemTokens {
+        redeemer::accounts::RedeemTokens {
+            redeemer: self.redeemer,
+            tokens: redeemer::accounts::MutTokenPair {
+                iou_mint: self.iou_mint,
+                redemption_mint: self.redemption_mint,
+                redemption_vault: self.redemption_vault,
+                token_program: spl_token::ID,
+            },
+            source_authority: *source_authority,
+            iou_source: *iou_source,
+            redemption_destination: *redemption_destination,
+        }
+    }
+
+    /// Address of the [redeemer::RedemptionVesting] of `beneficiary`.
+    pub fn redemption_vesting(&self, beneficiary: &Pubkey) -> Pubkey {
+        let (vesting, _) = Pubkey::find_program_address(
+            &[
+                b"RedemptionVesting",
+                self.redeemer.as_ref(),
+                beneficiary.as_ref(),
+            ],
+            &redeemer::ID,
+        );
+        vesting
+    }
+
+    /// Accounts of a redemption minting through `proxy`, of which the
+    /// redeemer must be a minter.
+    pub fn redeem_tokens_from_mint_proxy(
+        &self,
+        proxy: &MintProxy,
+        source_authority: &Pubkey,
+        iou_source: &Pubkey,
+        redemption_destination: &Pubkey,
+    ) -> redeemer::accounts::RedeemTokensFromMintProxyV2 {
+        redeemer::accounts::RedeemTokensFromMintProxyV2 {
+            redeem_ctx: self.redeem_tokens(source_authority, iou_source, redemption_destination),
+            mint_proxy_config: proxy.config,
+            proxy_mint_authority: proxy.proxy_mint_authority,
+            mint_proxy_program: mint_proxy::ID,
+            minter_info: proxy.minter_info(&self.redeemer),
+        }
+    }
+}
+
+impl TestEnv {
+    /// Creates a redeemer paying `numerator / denominator` redemption tokens
+    /// per IOU token, administered by `admin`. Signed by the mint authority of
+    /// the IOU token.
+    pub async fn create_redeemer(
+        &mut self,
+        iou_mint: &Pubkey,
+        iou_mint_authority: &Keypair,
+        redemption_mint: &Pubkey,
+        numerator: u64,
+        denominator: u64,
+        admin: &Pubkey,
+    ) -> Result<Redeemer> {
+        let (redeemer, _) = Pubkey::find_program_address(
+            &[b"Redeemer", iou_mint.as_ref(), redemption_mint.as_ref()],
+            &redeemer::ID,
+        );
+        let redemption_vault = self
+            .create_token_account(redemption_mint, &redeemer)
+            .await?;
+
+        let ix = instruction(
+            redeemer::ID,
+            redeemer::accounts::CreateRedeemerWithRate {
+                create: redeemer::accounts::CreateRedeemer {
+                    redeemer,
+                    tokens: redeemer::accounts::ReadonlyTokenPair {
+                        iou_mint: *iou_mint,
+                        redemption_mint: *redemption_mint,
+                        redemption_vault,
+                    },
+                    payer: self.payer(),
+                    system_program: system_program::ID,
+                },
+                iou_mint_authority: iou_mint_authority.pubkey(),
+            },
+            redeemer::instruction::CreateRedeemerWithRate {
+                numerator,
+                denominator,
+                admin: *admin,
+            },
+        );
+        self.process(&[ix], &[iou_mint_authority]).await?;
+
+        Ok(Redeemer {
+            redeemer,
+            iou_mint: *iou_mint,
+            redemption_mint: *redemption_mint,
+            redemption_vault,
+        })
+    }
+
+    /// Redeems `amount` IOU tokens from `iou_source`, owned or delegated to
+    /// `source_authority`, out of the redemption vault.
+    pub async fn redeem_tokens(
+        &mut self,
+        redeemer: &Redeemer,
+        source_authority: &Keypair,
+        iou_source: &Pubkey,
+        redemption_destination: &Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        let ix = instruction(
+            redeemer::ID,
+            redeemer.redeem_tokens(
+                &source_authority.pubkey(),
+                iou_source,
+                redemption_destination,
+            ),
+            redeemer::instruction::RedeemTokens { amount },
+        );
+        self.process(&[ix], &[source_authority]).await
+    }
+
+    /// Makes redeemed tokens vest over `vesting_duration`.
+    pub async fn set_vesting_duration(
+        &mut self,
+        redeemer: &Redeemer,
+        admin: &Keypair,
+        vesting_duration: i64,
+    ) -> Result<()> {
+        let ix = instruction(
+            redeemer::ID,
+            redeemer::accounts::RedeemerAdmin {
+                redeemer: redeemer.redeemer,
+                admin: admin.pubkey(),
+            },
+            redeemer::instruction::SetVestingDuration { vesting_duration },
+        );
+        self.process(&[ix], &[admin]).await
+    }
+
+    /// Creates the [redeemer::RedemptionVesting] of `beneficiary`.
+    /// Returns its address.
+    pub async fn create_redemption_vesting(
+        &mut self,
+        redeemer: &Redeemer,
+        beneficiary: &Pubkey,
+    ) -> Result<Pubkey> {
+        let vesting = redeemer.redemption_vesting(beneficiary);
+        let ix = instruction(
+            redeemer::ID,
+            redeemer::accounts::CreateRedemptionVesting {
+                redeemer: redeemer.redeemer,
+                beneficiary: *beneficiary,
+                vesting,
+                payer: self.payer(),
+                system_program: system_program::ID,
+            },
+            redeemer::instruction::CreateRedemptionVesting {},
+        );
+        self.process(&[ix], &[]).await?;
+        Ok(vesting)
+    }
+
+    /// Redeems `amount` IOU tokens from `iou_source` into `vesting`, to be
+    /// minted through `proxy`.
+    pub async fn redeem_tokens_vested(
+        &mut self,
+        redeemer: &Redeemer,
+        proxy: &MintProxy,
+        source_authority: &Keypair,
+        iou_source: &Pubkey,
+        vesting: &Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        let ix = instruction(
+            redeemer::ID,
+            redeemer::accounts::RedeemTokensVested {
+                redeemer: redeemer.redeemer,
+                tokens: redeemer::accounts::MutTokenPair {
+                    iou_mint: redeemer.iou_mint,
+                    redemption_mint: redeemer.redemption_mint,
+                    redemption_vault: redeemer.redemption_vault,
+                    token_program: spl_token::ID,
+                },
+                source_authority: source_authority.pubkey(),
+                iou_source: *iou_source,
+                vesting: *vesting,
+                mint_proxy_config: proxy.config,
+                minter_info: proxy.minter_info(&redeemer.redeemer),
+            },
+            redeemer::instruction::RedeemTokensVested { amount },
+        );
+        self.process(&[ix], &[source_authority]).await
+    }
+
+    /// Mints the vested tokens of the [redeemer::RedemptionVesting] of
+    /// `beneficiary` through `proxy` into `destination`.
+    pub async fn withdraw_vested(
+        &mut self,
+        redeemer: &Redeemer,
+        proxy: &MintProxy,
+        beneficiary: &Keypair,
+        destination: &Pubkey,
+    ) -> Result<()> {
+        let ix = instruction(
+            redeemer::ID,
+            redeemer::accounts::WithdrawVested {
+                redeemer: redeemer.redeemer,
+                beneficiary: beneficiary.pubkey(),
+                vesting: redeemer.redemption_vesting(&beneficiary.pubkey()),
+                redemption_mint: redeemer.redemption_mint,
+                destination: *destination,
+                mint_proxy_config: proxy.config,
+                proxy_mint_authority: proxy.proxy_mint_authority,
+                mint_proxy_program: mint_proxy::ID,
+                minter_info: proxy.minter_info(&redeemer.redeemer),
+                token_program: spl_token::ID,
+            },
+            redeemer::instruction::WithdrawVested {},
+        );
+        self.process(&[ix], &[beneficiary]).await
+    }
+}
@@ -0,0 +1,178 @@
+//! Lockup release fixtures.
+
+use anchor_lang::prelude::*;
+use lockup_manifest::{lockup_config_address, ManifestEntry, PlanConfig, ReleaseAddresses};
+use solana_sdk::signature::{Keypair, Signer};
+
+use crate::{instruction, MintProxy, Result, TestEnv};
+
+impl TestEnv {
+    /// Creates a [lockup::Release] of `amount` tokens of the proxy's mint to
+    /// `beneficiary`, vesting linearly from `start_ts` to `end_ts`.
+    pub async fn create_release(
+        &mut self,
+        proxy: &MintProxy,
+        beneficiary: &Pubkey,
+        amount: u64,
+        start_ts: i64,
+        end_ts: i64,
+    ) -> Result<ReleaseAddresses> {
+        let config = PlanConfig {
+            mint: proxy.mint,
+            owner: self.owner.pubkey(),
+            payer: self.payer(),
+        };
+        let entry = ManifestEntry {
+            beneficiary: *beneficiary,
+            amount,
+            start_ts,
+            end_ts,
+        };
+        self.process_as_owner(
+            &[
+                lockup_manifest::minter_add(&config, &entry),
+                lockup_manifest::create_release(&config, &entry),
+            ],
+            &[],
+        )
+        .await?;
+        Ok(entry.addresses(&proxy.mint))
+    }
+
+    /// Withdraws the vested tokens of the beneficiary's release into `token_account`.
+    pub async fn withdraw_release(
+        &mut self,
+        proxy: &MintProxy,
+        beneficiary: &Keypair,
+        token_account: &Pubkey,
+    ) -> Result<()> {
+        let addresses = ReleaseAddresses::derive(&proxy.mint, &beneficiary.pubkey());
+        let ix = instruction(
+            lockup::ID,
+            lockup::accounts::WithdrawV2 {
+                proxy_mint_authority: proxy.proxy_mint_authority,
+                token_mint: proxy.mint,
+                beneficiary: beneficiary.pubkey(),
+                release: addresses.release,
+                token_account: *token_account,
+                token_program: spl_token::ID,
+                minter_info: addresses.minter_info,
+                mint_proxy_program: mint_proxy::ID,
+                mint_proxy_config: proxy.config,
+            },
+            lockup::instruction::WithdrawV2 {},
+        );
+        self.process(&[ix], &[beneficiary]).await
+    }
+
+    /// Creates an escrow-backed [lockup::Release] of `amount` tokens of `mint`
+    /// to `beneficiary`, funded from `source` and vesting linearly from
+    /// `start_ts` to `end_ts`. Unvested tokens return to `treasury` on revocation.
+    ///
+    /// Returns the [lockup::Release] and its vault.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_escrow_release(
+        &mut self,
+        mint: &Pubkey,
+        beneficiary: &Pubkey,
+        source: &Pubkey,
+        source_authority: &Keypair,
+        treasury: &Pubkey,
+        amount: u64,
+        start_ts: i64,
+        end_ts: i64,
+    ) -> Result<(Pubkey, Pubkey)> {
+        let release = lockup_manifest::release_address(beneficiary);
+        let vault = self.create_token_account(mint, &release).await?;
+        let ix = instruction(
+            lockup::ID,
+            lockup::accounts::CreateEscrowReleaseV2 {
+                config: lockup_config_address(),
+                inner: lockup::accounts::CreateEscrowRelease {
+                    auth: lockup::accounts::Auth {
+                        owner: self.owner.pubkey(),
+                    },
+                    beneficiary: *beneficiary,
+                    release,
+                    escrow: escrow_address(&release),
+                    mint: *mint,
+                    vault,
+                    treasury: *treasury,
+                    source: *source,
+                    source_authority: source_authority.pubkey(),
+                    payer: self.payer(),
+                    token_program: spl_token::ID,
+                    system_program: solana_sdk::system_program::ID,
+                    rent: solana_sdk::sysvar::rent::ID,
+                },
+            },
+            lockup::instruction::CreateEscrowReleaseV2 {
+                release_amount: amount,
+                start_ts,
+                end_ts,
+            },
+        );
+        self.process_as_owner(&[ix], &[source_authority]).await?;
+        Ok((release, vault))
+    }
+
+    /// Revokes the escrow-backed release of `beneficiary`, returning its
+    /// unvested tokens to `treasury`.
+    pub async fn revoke_escrow_release(
+        &mut self,
+        beneficiary: &Pubkey,
+        vault: &Pubkey,
+        treasury: &Pubkey,
+    ) -> Result<()> {
+        let release = lockup_manifest::release_address(beneficiary);
+        let ix = instruction(
+            lockup::ID,
+            lockup::accounts::RevokeEscrowReleaseV2 {
+                config: lockup_config_address(),
+                inner: lockup::accounts::RevokeEscrowRelease {
+                    auth: lockup::accounts::Auth {
+                        owner: self.owner.pubkey(),
+                    },
+                    release,
+                    escrow: escrow_address(&release),
+                    vault: *vault,
+                    treasury: *treasury,
+                    token_program: spl_token::ID,
+                },
+            },
+            lockup::instruction::RevokeEscrowReleaseV2 {},
+        );
+        self.process_as_owner(&[ix], &[]).await
+    }
+
+    /// Withdraws the vested tokens of the beneficiary's escrow-backed release
+    /// into `token_account`.
+    pub async fn withdraw_escrow(
+        &mut self,
+        beneficiary: &Keypair,
+        vault: &Pubkey,
+        token_account: &Pubkey,
+    ) -> Result<()> {
+        let release = lockup_manifest::release_address(&beneficiary.pubkey());
+        let ix = instruction(
+            lockup::ID,
+            lockup::accounts::WithdrawEscrow {
+                beneficiary: beneficiary.pubkey(),
+                release,
+                escrow: escrow_address(&release),
+                vault: *vault,
+                token_account: *token_account,
+                token_program: spl_token::ID,
+            },
+            lockup::instruction::WithdrawEscrowV2 {},
+        );
+        self.process(&[ix], &[beneficiary]).await
+    }
+}
+
+/// Address of the [lockup::ReleaseEscrow] of a [lockup::Release].
+fn escrow_address(release: &Pubkey) -> Pubkey {
+    let (escrow, _) =
+        Pubkey::find_program_address(&[b"ReleaseEscrow", release.as_ref()], &lockup::ID);
+    escrow
+}
@@ -0,0 +1,235 @@
+//! Continuation router route fixtures.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::sysvar;
+use solana_sdk::signature::{Keypair, Signer};
+
+use crate::{instruction, MintProxy, Pool, Redeemer, Result, TestEnv, Wrapper};
+
+/// Builds the instructions of a route through the continuation router.
+///
+/// The route is begun with [continuation_router::begin_v2] on a continuation
+/// account created by [TestEnv::begin_route] and finished by [RouteBuilder::build]
+/// with [continuation_router::end].
+#[derive(Clone, Debug)]
+pub struct RouteBuilder {
+    /// The continuation account.
+    pub continuation: Pubkey,
+    /// Owner of every token account of the route.
+    pub owner: Pubkey,
+    /// Token account the route is started from.
+    pub input: Pubkey,
+    /// Token account the route ends in.
+    pub output: Pubkey,
+    /// Amount of input tokens.
+    pub amount_in: u64,
+    /// Minimum amount of output tokens.
+    pub minimum_amount_out: u64,
+    steps: Vec<Instruction>,
+}
+
+impl RouteBuilder {
+    fn continuation_accounts(
+        &self,
+        swap_program: Pubkey,
+    ) -> continuation_router::accounts::ContinuationAccounts {
+        continuation_router::accounts::ContinuationAccounts {
+            continuation: self.continuation,
+            token_program: spl_token::ID,
+            swap_program,
+            owner: self.owner,
+        }
+    }
+
+    /// Swaps `user_input`, holding `input_mint`, into `user_output` via a StableSwap pool.
+    pub fn ss_swap(
+        mut self,
+        pool: &Pool,
+        input_mint: &Pubkey,
+        user_input: &Pubkey,
+        user_output: &Pubkey,
+    ) -> Self {
+        let (input, output) = pool.sides(input_mint);
+        // The nested [continuation_router::SSSwap] account structs are not
+        // exported to clients, so the action accounts are listed in their
+        // declaration order instead.
+        let mut ix = instruction(
+            continuation_router::ID,
+            self.continuation_accounts(stable_swap_anchor::ID),
+            continuation_router::instruction::SsSwap {},
+        );
+        ix.accounts.extend([
+            AccountMeta::new_readonly(pool.swap, false),
+            AccountMeta::new_readonly(pool.authority, false),
+            AccountMeta::new_readonly(sysvar::clock::ID, false),
+            AccountMeta::new(*user_input, false),
+            AccountMeta::new(input.reserve, false),
+            AccountMeta::new(*user_output, false),
+            AccountMeta::new(output.reserve, false),
+            AccountMeta::new(output.admin_fees, false),
+        ]);
+        self.steps.push(ix);
+        self
+    }
+
+    /// Wraps the underlying tokens of `user_underlying` into `user_wrapped`.
+    pub fn ad_deposit(
+        self,
+        wrapper: &Wrapper,
+        user_underlying: &Pubkey,
+        user_wrapped: &Pubkey,
+    ) -> Self {
+        let accounts = continuation_router::accounts::ADDepositAccounts {
+            continuation: self.continuation_accounts(add_decimals::ID),
+            action: continuation_router::accounts::ADDeposit {
+                input: *user_underlying,
+                output: *user_wrapped,
+            },
+        };
+        let user_stake = wrapper.user_stake(&self.owner, user_underlying, user_wrapped);
+        self.pass_through(
+            accounts,
+            continuation_router::instruction::AdDeposit {},
+            user_stake,
+        )
+    }
+
+    /// Unwraps the wrapped tokens of `user_wrapped` into `user_underlying`.
+    pub fn ad_withdraw(
+        self,
+        wrapper: &Wrapper,
+        user_wrapped: &Pubkey,
+        user_underlying: &Pubkey,
+    ) -> Self {
+        let accounts = continuation_router::accounts::ADWithdrawAccounts {
+            continuation: self.continuation_accounts(add_decimals::ID),
+            action: continuation_router::accounts::ADWithdraw {
+                input: *user_wrapped,
+                output: *user_underlying,
+            },
+        };
+        let user_stake = wrapper.user_stake(&self.owner, user_underlying, user_wrapped);
+        self.pass_through(
+            accounts,
+            continuation_router::instruction::AdWithdraw {},
+            user_stake,
+        )
+    }
+
+    /// Redeems the IOU tokens of `iou_source` into `redemption_destination`,
+    /// minting through `proxy` whatever the redemption vault cannot cover.
+    pub fn rd_redeem(
+        self,
+        redeemer: &Redeemer,
+        proxy: &MintProxy,
+        iou_source: &Pubkey,
+        redemption_destination: &Pubkey,
+    ) -> Self {
+        let accounts = continuation_router::accounts::RDRedeemAccounts {
+            continuation: self.continuation_accounts(redeemer::ID),
+            action: continuation_router::accounts::RDRedeem {
+                input: *iou_source,
+                output: *redemption_destination,
+            },
+        };
+        let redeem = redeemer.redeem_tokens_from_mint_proxy(
+            proxy,
+            &self.owner,
+            iou_source,
+            redemption_destination,
+        );
+        self.pass_through(
+            accounts,
+            continuation_router::instruction::RdRedeem {},
+            redeem,
+        )
+    }
+
+    /// Appends a pass-through action, whose target program receives
+    /// `remaining_accounts` as its instruction accounts.
+    fn pass_through(
+        mut self,
+        accounts: impl ToAccountMetas,
+        data: impl anchor_lang::InstructionData,
+        remaining_accounts: impl ToAccountMetas,
+    ) -> Self {
+        let mut ix = instruction(continuation_router::ID, accounts, data);
+        ix.accounts
+            .extend(remaining_accounts.to_account_metas(None));
+        self.steps.push(ix);
+        self
+    }
+
+    /// Number of actions in the route.
+    pub fn num_steps(&self) -> u16 {
+        self.steps.len() as u16
+    }
+
+    /// Builds the [continuation_router::begin_v2], action and
+    /// [continuation_router::end] instructions of the route.
+    pub fn build(self) -> Vec<Instruction> {
+        let begin = instruction(
+            continuation_router::ID,
+            continuation_router::accounts::BeginV2 {
+                continuation: self.continuation,
+                input: self.input,
+                output: self.output,
+                owner: self.owner,
+            },
+            continuation_router::instruction::BeginV2 {
+                amount_in: self.amount_in,
+                minimum_amount_out: self.minimum_amount_out,
+                num_steps: self.num_steps(),
+            },
+        );
+        let end = instruction(
+            continuation_router::ID,
+            continuation_router::accounts::End {
+                continuation: self.continuation,
+                output: self.output,
+                owner: self.owner,
+                payer: self.owner,
+            },
+            continuation_router::instruction::End {},
+        );
+        std::iter::once(begin)
+            .chain(self.steps)
+            .chain(std::iter::once(end))
+            .collect()
+    }
+}
+
+impl TestEnv {
+    /// Creates a continuation account and starts building a route of
+    /// `amount_in` tokens of `input` into `output`, both owned by `owner`.
+    pub async fn begin_route(
+        &mut self,
+        owner: &Pubkey,
+        input: &Pubkey,
+        output: &Pubkey,
+        amount_in: u64,
+        minimum_amount_out: u64,
+    ) -> Result<RouteBuilder> {
+        let continuation = self
+            .create_account(
+                8 + continuation_router::Continuation::LEN,
+                &continuation_router::ID,
+            )
+            .await?;
+        Ok(RouteBuilder {
+            continuation: continuation.pubkey(),
+            owner: *owner,
+            input: *input,
+            output: *output,
+            amount_in,
+            minimum_amount_out,
+            steps: vec![],
+        })
+    }
+
+    /// Processes a route in a single transaction signed by its owner.
+    pub async fn process_route(&mut self, route: RouteBuilder, owner: &Keypair) -> Result<()> {
+        self.process(&route.build(), &[owner]).await
+    }
+}
@@ -0,0 +1,144 @@
+//! Token fixtures.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::solana_program::system_instruction;
+use solana_sdk::signature::{Keypair, Signer};
+
+use crate::{Result, TestEnv};
+
+impl TestEnv {
+    /// Creates an account of `space` bytes owned by `owner`, funded to be rent exempt.
+    pub async fn create_account(&mut self, space: usize, owner: &Pubkey) -> Result<Keypair> {
+        let account = Keypair::new();
+        let lamports = Rent::default().minimum_balance(space);
+        self.process(
+            &[system_instruction::create_account(
+                &self.payer(),
+                &account.pubkey(),
+                lamports,
+                space as u64,
+                owner,
+            )],
+            &[&account],
+        )
+        .await?;
+        Ok(account)
+    }
+
+    /// Creates a mint.
+    pub async fn create_mint(
+        &mut self,
+        authority: &Pubkey,
+        freeze_authority: Option<&Pubkey>,
+        decimals: u8,
+    ) -> Result<Pubkey> {
+        let mint = self
+            .create_account(spl_token::state::Mint::LEN, &spl_token::ID)
+            .await?;
+        self.process(
+            &[spl_token::instruction::initialize_mint(
+                &spl_token::ID,
+                &mint.pubkey(),
+                authority,
+                freeze_authority,
+                decimals,
+            )
+            .unwrap()],
+            &[],
+        )
+        .await?;
+        Ok(mint.pubkey())
+    }
+
+    /// Creates a token account of `mint` owned by `owner`.
+    pub async fn create_token_account(&mut self, mint: &Pubkey, owner: &Pubkey) -> Result<Pubkey> {
+        let account = self
+            .create_account(spl_token::state::Account::LEN, &spl_token::ID)
+            .await?;
+        self.process(
+            &[spl_token::instruction::initialize_account(
+                &spl_token::ID,
+                &account.pubkey(),
+                mint,
+                owner,
+            )
+            .unwrap()],
+            &[],
+        )
+        .await?;
+        Ok(account.pubkey())
+    }
+
+    /// Mints `amount` tokens of `mint` to `destination`.
+    pub async fn mint_to(
+        &mut self,
+        mint: &Pubkey,
+        authority: &Keypair,
+        destination: &Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        self.process(
+            &[spl_token::instruction::mint_to(
+                &spl_token::ID,
+                mint,
+                destination,
+                &authority.pubkey(),
+                &[],
+                amount,
+            )
+            .unwrap()],
+            &[authority],
+        )
+        .await
+    }
+
+    /// Transfers `amount` tokens from `source` to `destination`.
+    pub async fn transfer(
+        &mut self,
+        source: &Pubkey,
+        authority: &Keypair,
+        destination: &Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        self.process(
+            &[spl_token::instruction::transfer(
+                &spl_token::ID,
+                source,
+                destination,
+                &authority.pubkey(),
+                &[],
+                amount,
+            )
+            .unwrap()],
+            &[authority],
+        )
+        .await
+    }
+
+    /// Fetches a mint.
+    pub async fn mint(&mut self, mint: &Pubkey) -> spl_token::state::Mint {
+        let account = self
+            .context
+            .banks_client
+            .get_account(*mint)
+            .await
+            .unwrap()
+            .unwrap_or_else(|| panic!("mint {} not found", mint));
+        spl_token::state::Mint::unpack(&account.data).unwrap()
+    }
+
+    /// Balance of a token account.
+    pub async fn token_balance(&mut self, account: &Pubkey) -> u64 {
+        let account = self
+            .context
+            .banks_client
+            .get_account(*account)
+            .await
+            .unwrap()
+            .unwrap_or_else(|| panic!("token account {} not found", account));
+        spl_token::state::Account::unpack(&account.data)
+            .unwrap()
+            .amount
+    }
+}
@@ -0,0 +1,99 @@
+//! add-decimals wrapper fixtures.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{system_program, sysvar};
+use solana_sdk::signature::{Keypair, Signer};
+
+use crate::{instruction, Result, TestEnv};
+
+/// An add-decimals wrapper of an underlying token.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Wrapper {
+    /// The [add_decimals::WrappedToken] PDA.
+    pub wrapper: Pubkey,
+    /// Mint of the wrapped token.
+    pub wrapper_mint: Pubkey,
+    /// Underlying tokens held by the wrapper.
+    pub wrapper_underlying_tokens: Pubkey,
+    /// Mint of the underlying token.
+    pub underlying_mint: Pubkey,
+}
+
+impl Wrapper {
+    /// Accounts of a deposit or withdrawal by `owner`.
+    pub fn user_stake(
+        &self,
+        owner: &Pubkey,
+        user_underlying_tokens: &Pubkey,
+        user_wrapped_tokens: &Pubkey,
+    ) -> add_decimals::accounts::UserStake {
+        add_decimals::accounts::UserStake {
+            wrapper: self.wrapper,
+            wrapper_mint: self.wrapper_mint,
+            wrapper_underlying_tokens: self.wrapper_underlying_tokens,
+            owner: *owner,
+            user_underlying_tokens: *user_underlying_tokens,
+            user_wrapped_tokens: *user_wrapped_tokens,
+            token_program: spl_token::ID,
+        }
+    }
+}
+
+impl TestEnv {
+    /// Creates a wrapper of `underlying_mint` with `decimals` decimals.
+    pub async fn create_wrapper(
+        &mut self,
+        underlying_mint: &Pubkey,
+        decimals: u8,
+    ) -> Result<Wrapper> {
+        let (wrapper, nonce) = Pubkey::find_program_address(
+            &[b"anchor", underlying_mint.as_ref(), &[decimals]],
+            &add_decimals::ID,
+        );
+        let wrapper_mint = self.create_mint(&wrapper, Some(&wrapper), decimals).await?;
+        let wrapper_underlying_tokens =
+            self.create_token_account(underlying_mint, &wrapper).await?;
+
+        let ix = instruction(
+            add_decimals::ID,
+            add_decimals::accounts::InitializeWrapper {
+                wrapper,
+                wrapper_underlying_tokens,
+                underlying_mint: *underlying_mint,
+                wrapper_mint,
+                payer: self.payer(),
+                rent: sysvar::rent::ID,
+                system_program: system_program::ID,
+            },
+            add_decimals::instruction::InitializeWrapper { _nonce: nonce },
+        );
+        self.process(&[ix], &[]).await?;
+
+        Ok(Wrapper {
+            wrapper,
+            wrapper_mint,
+            wrapper_underlying_tokens,
+            underlying_mint: *underlying_mint,
+        })
+    }
+
+    /// Wraps `amount` underlying tokens of `user_underlying`, owned by `owner`,
+    /// into `user_wrapped`.
+    pub async fn wrap(
+        &mut self,
+        wrapper: &Wrapper,
+        owner: &Keypair,
+        user_underlying: &Pubkey,
+        user_wrapped: &Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        let ix = instruction(
+            add_decimals::ID,
+            wrapper.user_stake(&owner.pubkey(), user_underlying, user_wrapped),
+            add_decimals::instruction::Deposit {
+                deposit_amount: amount,
+            },
+        );
+        self.process(&[ix], &[owner]).await
+    }
+}
@@ -0,0 +1,100 @@
+use periphery_test::*;
+use solana_sdk::signature::{Keypair, Signer};
+
+#[tokio::test]
+async fn test_allowance_proposal() {
+    let mut env = TestEnv::start().await;
+    let proxy = env.create_mint_proxy(6, u64::MAX).await.unwrap();
+    let minter = Keypair::new().pubkey();
+    let minter_info = env.add_minter(&proxy, &minter, 1_000).await.unwrap();
+
+    let approvers = [Keypair::new(), Keypair::new(), Keypair::new()];
+    env.set_approvers(
+        &proxy,
+        approvers.iter().map(|a| a.pubkey()).collect(),
+        2,
+        500,
+        &[],
+    )
+    .await
+    .unwrap();
+
+    // the owner alone may no longer lower the threshold
+    let now = env.now().await;
+    env.set_timestamp(now + 1).await;
+    let approver_keys: Vec<_> = approvers.iter().map(|a| a.pubkey()).collect();
+    assert!(env
+        .set_approvers(&proxy, approver_keys.clone(), 0, 500, &[])
+        .await
+        .is_err());
+    assert!(env
+        .set_approvers(&proxy, approver_keys.clone(), 0, 500, &[&approvers[0]])
+        .await
+        .is_err());
+
+    let proposal = env
+        .propose_allowance(&proxy, &minter, 1_000_000)
+        .await
+        .unwrap();
+    // not an approver
+    assert!(env
+        .approve_allowance(&proxy, &proposal, &Keypair::new())
+        .await
+        .is_err());
+    env.approve_allowance(&proxy, &proposal, &approvers[0])
+        .await
+        .unwrap();
+    // below the threshold
+    assert!(env
+        .execute_allowance(&proxy, &proposal, &minter)
+        .await
+        .is_err());
+
+    let now = env.now().await;
+    env.set_timestamp(now + 1).await;
+    // approving twice does not count twice
+    assert!(env
+        .approve_allowance(&proxy, &proposal, &approvers[0])
+        .await
+        .is_err());
+    env.approve_allowance(&proxy, &proposal, &approvers[1])
+        .await
+        .unwrap();
+    env.execute_allowance(&proxy, &proposal, &minter)
+        .await
+        .unwrap();
+
+    let info: mint_proxy::MinterInfo = env.account(&minter_info).await;
+    assert_eq!(info.allowance, 1_000_000);
+}
+
+#[tokio::test]
+async fn test_allowance_increase_limit() {
+    let mut env = TestEnv::start().await;
+    let proxy = env.create_mint_proxy(6, u64::MAX).await.unwrap();
+    let minter = Keypair::new().pubkey();
+    let approver = Keypair::new();
+    env.set_approvers(&proxy, vec![approver.pubkey()], 1, 500, &[])
+        .await
+        .unwrap();
+
+    assert!(env.add_minter(&proxy, &minter, 501).await.is_err());
+    env.add_minter(&proxy, &minter, 300).await.unwrap();
+
+    // repeated updates share the limit
+    assert!(env.update_minter(&proxy, &minter, 600).await.is_err());
+    env.update_minter(&proxy, &minter, 500).await.unwrap();
+    let now = env.now().await;
+    env.set_timestamp(now + 1).await;
+    assert!(env.update_minter(&proxy, &minter, 501).await.is_err());
+
+    // removing and adding the minter again shares the limit too
+    env.remove_minter(&proxy, &minter).await.unwrap();
+    assert!(env.add_minter(&proxy, &minter, 1).await.is_err());
+
+    // the limit is restored in the next window
+    let now = env.now().await;
+    env.set_timestamp(now + mint_proxy::ALLOWANCE_INCREASE_WINDOW)
+        .await;
+    env.add_minter(&proxy, &minter, 500).await.unwrap();
+}
@@ -0,0 +1,127 @@
+use periphery_test::*;
+use solana_sdk::signature::{Keypair, Signer};
+
+#[tokio::test]
+async fn test_revoke_escrow_keeps_vested() {
+    let mut env = TestEnv::start().await;
+    let mint_authority = Keypair::new();
+    let mint = env
+        .create_mint(&mint_authority.pubkey(), None, 6)
+        .await
+        .unwrap();
+    let funder = Keypair::new();
+    let source = env
+        .create_token_account(&mint, &funder.pubkey())
+        .await
+        .unwrap();
+    env.mint_to(&mint, &mint_authority, &source, 1_000_000)
+        .await
+        .unwrap();
+    let treasury = env
+        .create_token_account(&mint, &funder.pubkey())
+        .await
+        .unwrap();
+
+    let beneficiary = Keypair::new();
+    let destination = env
+        .create_token_account(&mint, &beneficiary.pubkey())
+        .await
+        .unwrap();
+    let start_ts = env.now().await;
+    let end_ts = start_ts + 1_000;
+    let (release, vault) = env
+        .create_escrow_release(
+            &mint,
+            &beneficiary.pubkey(),
+            &source,
+            &funder,
+            &treasury,
+            1_000_000,
+            start_ts,
+            end_ts,
+        )
+        .await
+        .unwrap();
+    assert_eq!(env.token_balance(&vault).await, 1_000_000);
+
+    env.set_timestamp(start_ts + 250).await;
+    env.withdraw_escrow(&beneficiary, &vault, &destination)
+        .await
+        .unwrap();
+    assert_eq!(env.token_balance(&destination).await, 250_000);
+
+    // Half has vested, of which half was withdrawn.
+    env.set_timestamp(start_ts + 500).await;
+    env.revoke_escrow_release(&beneficiary.pubkey(), &vault, &treasury)
+        .await
+        .unwrap();
+    assert_eq!(env.token_balance(&treasury).await, 500_000);
+    assert_eq!(env.token_balance(&vault).await, 250_000);
+
+    let revoked: lockup::Release = env.account(&release).await;
+    assert_eq!(revoked.start_balance, 500_000);
+    assert_eq!(revoked.outstanding, 250_000);
+    assert_eq!(revoked.end_ts, start_ts + 500);
+
+    // The vested but unwithdrawn tokens remain claimable after revocation.
+    env.set_timestamp(end_ts + 1).await;
+    env.withdraw_escrow(&beneficiary, &vault, &destination)
+        .await
+        .unwrap();
+    assert_eq!(env.token_balance(&destination).await, 500_000);
+    assert_eq!(env.token_balance(&vault).await, 0);
+    assert_eq!(env.token_balance(&treasury).await, 500_000);
+}
+
+#[tokio::test]
+async fn test_revoke_escrow_before_start() {
+    let mut env = TestEnv::start().await;
+    let mint_authority = Keypair::new();
+    let mint = env
+        .create_mint(&mint_authority.pubkey(), None, 6)
+        .await
+        .unwrap();
+    let funder = Keypair::new();
+    let source = env
+        .create_token_account(&mint, &funder.pubkey())
+        .await
+        .unwrap();
+    env.mint_to(&mint, &mint_authority, &source, 1_000_000)
+        .await
+        .unwrap();
+    let treasury = env
+        .create_token_account(&mint, &funder.pubkey())
+        .await
+        .unwrap();
+
+    let beneficiary = Keypair::new();
+    let destination = env
+        .create_token_account(&mint, &beneficiary.pubkey())
+        .await
+        .unwrap();
+    let now = env.now().await;
+    let (_, vault) = env
+        .create_escrow_release(
+            &mint,
+            &beneficiary.pubkey(),
+            &source,
+            &funder,
+            &treasury,
+            1_000_000,
+            now + 100,
+            now + 200,
+        )
+        .await
+        .unwrap();
+
+    env.revoke_escrow_release(&beneficiary.pubkey(), &vault, &treasury)
+        .await
+        .unwrap();
+    assert_eq!(env.token_balance(&treasury).await, 1_000_000);
+
+    env.set_timestamp(now + 300).await;
+    env.withdraw_escrow(&beneficiary, &vault, &destination)
+        .await
+        .unwrap();
+    assert_eq!(env.token_balance(&destination).await, 0);
+}
@@ -0,0 +1,75 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use periphery_test::*;
+use solana_sdk::account::{Account, AccountSharedData};
+use solana_sdk::signature::{Keypair, Signer};
+
+/// Rewrites the config of `proxy` as the SBR proxy, and the [mint_proxy::MinterInfo]
+/// of `minter` in the layout written by the deprecated state.
+async fn make_legacy_minter(env: &mut TestEnv, proxy: &MintProxy, minter: &Pubkey) -> Pubkey {
+    let config_account = env
+        .context
+        .banks_client
+        .get_account(proxy.config)
+        .await
+        .unwrap()
+        .unwrap();
+    let mut config =
+        mint_proxy::MintProxyConfig::try_deserialize(&mut config_account.data.as_slice()).unwrap();
+    config.state_associated_account = mint_proxy::PROXY_STATE_ACCOUNT;
+    let mut data = config_account.data.clone();
+    config.try_serialize(&mut data.as_mut_slice()).unwrap();
+    env.context.set_account(
+        &proxy.config,
+        &AccountSharedData::from(Account {
+            data,
+            ..config_account
+        }),
+    );
+
+    let minter_info = proxy.minter_info(minter);
+    let mut data = mint_proxy::MinterInfo::discriminator().to_vec();
+    data.extend(minter.to_bytes());
+    data.extend(1_000_u64.to_le_bytes());
+    data.push(255);
+    let account = Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: mint_proxy::ID,
+        executable: false,
+        rent_epoch: 0,
+    };
+    env.context
+        .set_account(&minter_info, &AccountSharedData::from(account));
+    minter_info
+}
+
+#[tokio::test]
+async fn test_pause_legacy_minter() {
+    let mut env = TestEnv::start().await;
+    let proxy = env.create_mint_proxy(6, u64::MAX).await.unwrap();
+    let minter = Keypair::new().pubkey();
+    env.add_minter(&proxy, &minter, 1_000).await.unwrap();
+    let minter_info = make_legacy_minter(&mut env, &proxy, &minter).await;
+
+    let info: mint_proxy::VersionedMinterInfo = env.account(&minter_info).await;
+    assert!(info.is_legacy());
+
+    // pausing does not wait for someone to migrate the minter
+    env.pause_minter(&proxy, &minter).await.unwrap();
+
+    let info: mint_proxy::VersionedMinterInfo = env.account(&minter_info).await;
+    assert!(!info.is_legacy());
+    assert!(info.paused);
+    assert_eq!(info.minter, minter);
+    assert_eq!(info.allowance, 1_000);
+    let account = env
+        .context
+        .banks_client
+        .get_account(minter_info)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(account.data.len(), 8 + mint_proxy::MinterInfo::LEN);
+    assert!(account.lamports >= Rent::default().minimum_balance(account.data.len()));
+}
@@ -0,0 +1,200 @@
+use periphery_test::*;
+use solana_sdk::signature::{Keypair, Signer};
+
+/// A redeemer minting 1:1 through a mint proxy, whose redemptions vest over 1,000 seconds.
+async fn vesting_redeemer(env: &mut TestEnv, iou_authority: &Keypair) -> (MintProxy, Redeemer) {
+    let proxy = env.create_mint_proxy(6, u64::MAX).await.unwrap();
+    let iou_mint = env
+        .create_mint(&iou_authority.pubkey(), None, 6)
+        .await
+        .unwrap();
+    let admin = Keypair::new();
+    let redeemer = env
+        .create_redeemer(&iou_mint, iou_authority, &proxy.mint, 1, 1, &admin.pubkey())
+        .await
+        .unwrap();
+    env.add_minter(&proxy, &redeemer.redeemer, 1_000_000)
+        .await
+        .unwrap();
+    env.set_vesting_duration(&redeemer, &admin, 1_000)
+        .await
+        .unwrap();
+    (proxy, redeemer)
+}
+
+#[tokio::test]
+async fn test_redeem_tokens_vested() {
+    let mut env = TestEnv::start().await;
+    let iou_authority = Keypair::new();
+    let (proxy, redeemer) = vesting_redeemer(&mut env, &iou_authority).await;
+
+    let user = Keypair::new();
+    let user_iou = env
+        .create_token_account(&redeemer.iou_mint, &user.pubkey())
+        .await
+        .unwrap();
+    let user_redemption = env
+        .create_token_account(&proxy.mint, &user.pubkey())
+        .await
+        .unwrap();
+    env.mint_to(&redeemer.iou_mint, &iou_authority, &user_iou, 2_000)
+        .await
+        .unwrap();
+    let vesting = env
+        .create_redemption_vesting(&redeemer, &user.pubkey())
+        .await
+        .unwrap();
+
+    // liquid redemptions are disabled while redemptions vest
+    assert!(env
+        .redeem_tokens(&redeemer, &user, &user_iou, &user_redemption, 1_000)
+        .await
+        .is_err());
+
+    let start_ts = env.now().await;
+    env.redeem_tokens_vested(&redeemer, &proxy, &user, &user_iou, &vesting, 1_000)
+        .await
+        .unwrap();
+    assert_eq!(env.token_balance(&user_iou).await, 1_000);
+
+    env.set_timestamp(start_ts + 250).await;
+    env.withdraw_vested(&redeemer, &proxy, &user, &user_redemption)
+        .await
+        .unwrap();
+    assert_eq!(env.token_balance(&user_redemption).await, 250);
+
+    env.set_timestamp(start_ts + 1_001).await;
+    env.withdraw_vested(&redeemer, &proxy, &user, &user_redemption)
+        .await
+        .unwrap();
+    assert_eq!(env.token_balance(&user_redemption).await, 1_000);
+
+    let vesting: redeemer::RedemptionVesting = env.account(&vesting).await;
+    assert_eq!(vesting.outstanding, 0);
+    assert_eq!(vesting.claimable, 0);
+}
+
+#[tokio::test]
+async fn test_redeem_into_other_vesting() {
+    let mut env = TestEnv::start().await;
+    let iou_authority = Keypair::new();
+    let (proxy, redeemer) = vesting_redeemer(&mut env, &iou_authority).await;
+
+    let victim = Keypair::new();
+    let victim_iou = env
+        .create_token_account(&redeemer.iou_mint, &victim.pubkey())
+        .await
+        .unwrap();
+    env.mint_to(&redeemer.iou_mint, &iou_authority, &victim_iou, 1_000)
+        .await
+        .unwrap();
+    let victim_vesting = env
+        .create_redemption_vesting(&redeemer, &victim.pubkey())
+        .await
+        .unwrap();
+    env.redeem_tokens_vested(
+        &redeemer,
+        &proxy,
+        &victim,
+        &victim_iou,
+        &victim_vesting,
+        1_000,
+    )
+    .await
+    .unwrap();
+    let before: redeemer::RedemptionVesting = env.account(&victim_vesting).await;
+
+    // topping up would restart the vesting of the victim's unvested tokens
+    let attacker = Keypair::new();
+    let attacker_iou = env
+        .create_token_account(&redeemer.iou_mint, &attacker.pubkey())
+        .await
+        .unwrap();
+    env.mint_to(&redeemer.iou_mint, &iou_authority, &attacker_iou, 1)
+        .await
+        .unwrap();
+    let now = env.now().await;
+    env.set_timestamp(now + 500).await;
+    assert!(env
+        .redeem_tokens_vested(
+            &redeemer,
+            &proxy,
+            &attacker,
+            &attacker_iou,
+            &victim_vesting,
+            1
+        )
+        .await
+        .is_err());
+
+    let after: redeemer::RedemptionVesting = env.account(&victim_vesting).await;
+    assert_eq!(after.start_ts, before.start_ts);
+    assert_eq!(after.end_ts, before.end_ts);
+    assert_eq!(after.start_balance, 1_000);
+    assert_eq!(env.token_balance(&attacker_iou).await, 1);
+}
+
+#[tokio::test]
+async fn test_create_redeemer_requires_iou_mint_authority() {
+    let mut env = TestEnv::start().await;
+    let proxy = env.create_mint_proxy(6, u64::MAX).await.unwrap();
+    let iou_authority = Keypair::new();
+    let iou_mint = env
+        .create_mint(&iou_authority.pubkey(), None, 6)
+        .await
+        .unwrap();
+
+    // anyone else may not squat the redeemer of the pair
+    let squatter = Keypair::new();
+    assert!(env
+        .create_redeemer(
+            &iou_mint,
+            &squatter,
+            &proxy.mint,
+            1_000,
+            1,
+            &squatter.pubkey()
+        )
+        .await
+        .is_err());
+
+    let admin = Keypair::new().pubkey();
+    let redeemer = env
+        .create_redeemer(&iou_mint, &iou_authority, &proxy.mint, 1, 1, &admin)
+        .await
+        .unwrap();
+    let state: redeemer::Redeemer = env.account(&redeemer.redeemer).await;
+    assert_eq!(state.admin, admin);
+}
+
+#[tokio::test]
+async fn test_redeem_tokens_vested_over_capacity() {
+    let mut env = TestEnv::start().await;
+    let iou_authority = Keypair::new();
+    let (proxy, redeemer) = vesting_redeemer(&mut env, &iou_authority).await;
+
+    let user = Keypair::new();
+    let user_iou = env
+        .create_token_account(&redeemer.iou_mint, &user.pubkey())
+        .await
+        .unwrap();
+    env.mint_to(&redeemer.iou_mint, &iou_authority, &user_iou, 2_000_000)
+        .await
+        .unwrap();
+    let vesting = env
+        .create_redemption_vesting(&redeemer, &user.pubkey())
+        .await
+        .unwrap();
+
+    // the redeemer may only mint 1,000,000 tokens, so the IOUs must not be burned
+    assert!(env
+        .redeem_tokens_vested(&redeemer, &proxy, &user, &user_iou, &vesting, 1_000_001)
+        .await
+        .is_err());
+    assert_eq!(env.token_balance(&user_iou).await, 2_000_000);
+
+    env.redeem_tokens_vested(&redeemer, &proxy, &user, &user_iou, &vesting, 1_000_000)
+        .await
+        .unwrap();
+    assert_eq!(env.token_balance(&user_iou).await, 1_000_000);
+}
@@ -0,0 +1,187 @@
+use periphery_test::*;
+use solana_sdk::signature::{Keypair, Signer};
+
+/// 1 token of 9 decimals.
+const ONE: u64 = 1_000_000_000;
+
+#[tokio::test]
+async fn test_wrap_and_swap() {
+    let mut env = TestEnv::start().await;
+    let payer = clone_keypair(&env.context.payer);
+    let authority = Keypair::new();
+
+    // USDC-like token wrapped to 9 decimals, paired with a 9 decimal token
+    let underlying = env.create_mint(&authority.pubkey(), None, 6).await.unwrap();
+    let wrapper = env.create_wrapper(&underlying, 9).await.unwrap();
+    let other = env.create_mint(&authority.pubkey(), None, 9).await.unwrap();
+
+    let payer_underlying = env
+        .create_token_account(&underlying, &payer.pubkey())
+        .await
+        .unwrap();
+    let payer_wrapped = env
+        .create_token_account(&wrapper.wrapper_mint, &payer.pubkey())
+        .await
+        .unwrap();
+    let payer_other = env
+        .create_token_account(&other, &payer.pubkey())
+        .await
+        .unwrap();
+    env.mint_to(
+        &underlying,
+        &authority,
+        &payer_underlying,
+        1_000_000 * 1_000_000,
+    )
+    .await
+    .unwrap();
+    env.mint_to(&other, &authority, &payer_other, 1_000_000 * ONE)
+        .await
+        .unwrap();
+    env.wrap(
+        &wrapper,
+        &payer,
+        &payer_underlying,
+        &payer_wrapped,
+        1_000_000 * 1_000_000,
+    )
+    .await
+    .unwrap();
+    let (pool, _) = env
+        .create_pool(
+            &wrapper.wrapper_mint,
+            &payer_wrapped,
+            1_000_000 * ONE,
+            &other,
+            &payer_other,
+            1_000_000 * ONE,
+            &payer,
+        )
+        .await
+        .unwrap();
+
+    let user = Keypair::new();
+    let user_underlying = env
+        .create_token_account(&underlying, &user.pubkey())
+        .await
+        .unwrap();
+    let user_wrapped = env
+        .create_token_account(&wrapper.wrapper_mint, &user.pubkey())
+        .await
+        .unwrap();
+    let user_other = env
+        .create_token_account(&other, &user.pubkey())
+        .await
+        .unwrap();
+    env.mint_to(&underlying, &authority, &user_underlying, 100 * 1_000_000)
+        .await
+        .unwrap();
+
+    let route = env
+        .begin_route(
+            &user.pubkey(),
+            &user_underlying,
+            &user_other,
+            100 * 1_000_000,
+            99 * ONE,
+        )
+        .await
+        .unwrap()
+        .ad_deposit(&wrapper, &user_underlying, &user_wrapped)
+        .ss_swap(&pool, &wrapper.wrapper_mint, &user_wrapped, &user_other);
+    env.process_route(route, &user).await.unwrap();
+
+    assert_eq!(env.token_balance(&user_underlying).await, 0);
+    assert_eq!(env.token_balance(&user_wrapped).await, 0);
+    let amount_out = env.token_balance(&user_other).await;
+    assert!((99 * ONE..100 * ONE).contains(&amount_out));
+}
+
+#[tokio::test]
+async fn test_route_minimum_amount_out() {
+    let mut env = TestEnv::start().await;
+    let payer = clone_keypair(&env.context.payer);
+    let authority = Keypair::new();
+
+    let mint_a = env.create_mint(&authority.pubkey(), None, 9).await.unwrap();
+    let mint_b = env.create_mint(&authority.pubkey(), None, 9).await.unwrap();
+    let payer_a = env
+        .create_token_account(&mint_a, &payer.pubkey())
+        .await
+        .unwrap();
+    let payer_b = env
+        .create_token_account(&mint_b, &payer.pubkey())
+        .await
+        .unwrap();
+    env.mint_to(&mint_a, &authority, &payer_a, 1_100 * ONE)
+        .await
+        .unwrap();
+    env.mint_to(&mint_b, &authority, &payer_b, 1_000 * ONE)
+        .await
+        .unwrap();
+    let (pool, _) = env
+        .create_pool(
+            &mint_a,
+            &payer_a,
+            1_000 * ONE,
+            &mint_b,
+            &payer_b,
+            1_000 * ONE,
+            &payer,
+        )
+        .await
+        .unwrap();
+
+    let user_b = env
+        .create_token_account(&mint_b, &payer.pubkey())
+        .await
+        .unwrap();
+    let route = env
+        .begin_route(&payer.pubkey(), &payer_a, &user_b, 100 * ONE, 100 * ONE)
+        .await
+        .unwrap()
+        .ss_swap(&pool, &mint_a, &payer_a, &user_b);
+    assert!(env.process_route(route, &payer).await.is_err());
+    assert_eq!(env.token_balance(&payer_a).await, 100 * ONE);
+    assert_eq!(env.token_balance(&user_b).await, 0);
+}
+
+#[tokio::test]
+async fn test_redeem_route() {
+    let mut env = TestEnv::start().await;
+    let authority = Keypair::new();
+
+    let proxy = env.create_mint_proxy(6, u64::MAX).await.unwrap();
+    let iou_mint = env.create_mint(&authority.pubkey(), None, 6).await.unwrap();
+    let admin = env.payer();
+    let redeemer = env
+        .create_redeemer(&iou_mint, &authority, &proxy.mint, 1, 2, &admin)
+        .await
+        .unwrap();
+    env.add_minter(&proxy, &redeemer.redeemer, 1_000_000)
+        .await
+        .unwrap();
+
+    let user = Keypair::new();
+    let user_iou = env
+        .create_token_account(&iou_mint, &user.pubkey())
+        .await
+        .unwrap();
+    let user_redemption = env
+        .create_token_account(&proxy.mint, &user.pubkey())
+        .await
+        .unwrap();
+    env.mint_to(&iou_mint, &authority, &user_iou, 1_000)
+        .await
+        .unwrap();
+
+    let route = env
+        .begin_route(&user.pubkey(), &user_iou, &user_redemption, 1_000, 500)
+        .await
+        .unwrap()
+        .rd_redeem(&redeemer, &proxy, &user_iou, &user_redemption);
+    env.process_route(route, &user).await.unwrap();
+
+    assert_eq!(env.token_balance(&user_iou).await, 0);
+    assert_eq!(env.token_balance(&user_redemption).await, 500);
+}
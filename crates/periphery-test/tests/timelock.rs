@@ -0,0 +1,107 @@
+use periphery_test::*;
+use solana_sdk::signature::{Keypair, Signer};
+
+#[tokio::test]
+async fn test_set_delay_through_timelock() {
+    let mut env = TestEnv::start().await;
+    let admin = Keypair::new();
+    let timelock = env.create_timelock(&admin.pubkey(), 100).await.unwrap();
+
+    let now = env.now().await;
+    assert!(env
+        .queue_action(&timelock, &admin, set_delay_ix(&timelock, 50), now + 99)
+        .await
+        .is_err());
+    let eta = now + 100;
+    let action = env
+        .queue_action(&timelock, &admin, set_delay_ix(&timelock, 50), eta)
+        .await
+        .unwrap();
+
+    env.set_timestamp(eta - 1).await;
+    assert!(env.execute_action(&timelock, &action).await.is_err());
+
+    env.set_timestamp(eta).await;
+    env.execute_action(&timelock, &action).await.unwrap();
+    let state: timelock::Timelock = env.account(&timelock).await;
+    assert_eq!(state.delay, 50);
+
+    env.set_timestamp(eta + 1).await;
+    assert!(env.execute_action(&timelock, &action).await.is_err());
+}
+
+#[tokio::test]
+async fn test_set_admin_through_timelock() {
+    let mut env = TestEnv::start().await;
+    let admin = Keypair::new();
+    let timelock = env.create_timelock(&admin.pubkey(), 100).await.unwrap();
+    let next_admin = Keypair::new();
+
+    // The timelock can only sign through an executed action.
+    let mut unsigned = set_admin_ix(&timelock, &next_admin.pubkey());
+    unsigned.accounts[0].is_signer = false;
+    assert!(env.process(&[unsigned], &[]).await.is_err());
+
+    let eta = env.now().await + 100;
+    let action = env
+        .queue_action(
+            &timelock,
+            &admin,
+            set_admin_ix(&timelock, &next_admin.pubkey()),
+            eta,
+        )
+        .await
+        .unwrap();
+    env.set_timestamp(eta).await;
+    env.execute_action(&timelock, &action).await.unwrap();
+    let state: timelock::Timelock = env.account(&timelock).await;
+    assert_eq!(state.admin, next_admin.pubkey());
+
+    // Only the new admin may queue actions.
+    let eta = env.now().await + 100;
+    assert!(env
+        .queue_action(&timelock, &admin, set_delay_ix(&timelock, 0), eta)
+        .await
+        .is_err());
+    env.queue_action(&timelock, &next_admin, set_delay_ix(&timelock, 0), eta)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_cancel_action() {
+    let mut env = TestEnv::start().await;
+    let admin = Keypair::new();
+    let timelock = env.create_timelock(&admin.pubkey(), 100).await.unwrap();
+
+    let eta = env.now().await + 100;
+    let action = env
+        .queue_action(&timelock, &admin, set_delay_ix(&timelock, 0), eta)
+        .await
+        .unwrap();
+    assert!(env
+        .cancel_action(&timelock, &action, &Keypair::new())
+        .await
+        .is_err());
+    env.cancel_action(&timelock, &action, &admin).await.unwrap();
+
+    env.set_timestamp(eta).await;
+    assert!(env.execute_action(&timelock, &action).await.is_err());
+    let state: timelock::Timelock = env.account(&timelock).await;
+    assert_eq!(state.delay, 100);
+}
+
+#[tokio::test]
+async fn test_action_expires() {
+    let mut env = TestEnv::start().await;
+    let admin = Keypair::new();
+    let timelock = env.create_timelock(&admin.pubkey(), 100).await.unwrap();
+
+    let eta = env.now().await + 100;
+    let action = env
+        .queue_action(&timelock, &admin, set_delay_ix(&timelock, 0), eta)
+        .await
+        .unwrap();
+    env.set_timestamp(eta + timelock::GRACE_PERIOD + 1).await;
+    assert!(env.execute_action(&timelock, &action).await.is_err());
+}
@@ -0,0 +1,65 @@
+use periphery_test::*;
+use solana_sdk::signature::{Keypair, Signer};
+
+#[tokio::test]
+async fn test_release_vests_linearly() {
+    let mut env = TestEnv::start().await;
+    let proxy = env.create_mint_proxy(6, u64::MAX).await.unwrap();
+
+    let beneficiary = Keypair::new();
+    let destination = env
+        .create_token_account(&proxy.mint, &beneficiary.pubkey())
+        .await
+        .unwrap();
+    let start_ts = env.now().await;
+    let end_ts = start_ts + 1_000;
+    let addresses = env
+        .create_release(&proxy, &beneficiary.pubkey(), 1_000_000, start_ts, end_ts)
+        .await
+        .unwrap();
+
+    env.set_timestamp(start_ts + 250).await;
+    env.withdraw_release(&proxy, &beneficiary, &destination)
+        .await
+        .unwrap();
+    assert_eq!(env.token_balance(&destination).await, 250_000);
+
+    let release: lockup::Release = env.account(&addresses.release).await;
+    assert_eq!(release.outstanding, 750_000);
+
+    env.set_timestamp(end_ts + 1).await;
+    env.withdraw_release(&proxy, &beneficiary, &destination)
+        .await
+        .unwrap();
+    assert_eq!(env.token_balance(&destination).await, 1_000_000);
+
+    let release: lockup::Release = env.account(&addresses.release).await;
+    assert_eq!(release.outstanding, 0);
+}
+
+#[tokio::test]
+async fn test_release_before_start() {
+    let mut env = TestEnv::start().await;
+    let proxy = env.create_mint_proxy(6, u64::MAX).await.unwrap();
+
+    let beneficiary = Keypair::new();
+    let destination = env
+        .create_token_account(&proxy.mint, &beneficiary.pubkey())
+        .await
+        .unwrap();
+    let now = env.now().await;
+    env.create_release(
+        &proxy,
+        &beneficiary.pubkey(),
+        1_000_000,
+        now + 100,
+        now + 200,
+    )
+    .await
+    .unwrap();
+
+    env.withdraw_release(&proxy, &beneficiary, &destination)
+        .await
+        .unwrap();
+    assert_eq!(env.token_balance(&destination).await, 0);
+}
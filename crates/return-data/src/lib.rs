@@ -0,0 +1,133 @@
+//! Decodes the return data of Saber periphery view instructions.
+//!
+//! The runtime strips trailing zero bytes from return data, so the helpers
+//! here pad the data back out before deserializing it.
+#![deny(rustdoc::all)]
+#![allow(rustdoc::missing_doc_code_examples)]
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::MAX_RETURN_DATA;
+
+/// Prefix of the log line the runtime emits when a program sets return data.
+pub const PROGRAM_RETURN_LOG_PREFIX: &str = "Program return: ";
+
+/// Errors encountered while decoding return data.
+#[derive(Debug, thiserror::Error)]
+pub enum ReturnDataError {
+    #[error("return data was set by {actual}, expected {expected}")]
+    ProgramMismatch { expected: Pubkey, actual: Pubkey },
+    #[error("no return data was found")]
+    NotFound,
+    #[error("malformed return data log: {0}")]
+    MalformedLog(String),
+    #[error("return data exceeds {MAX_RETURN_DATA} bytes")]
+    TooLarge,
+    #[error("invalid base64: {0}")]
+    Base64(#[from] base64::DecodeError),
+    #[error("failed to deserialize return data: {0}")]
+    Deserialize(#[from] std::io::Error),
+}
+
+/// Decodes return data set by `program_id`.
+pub fn decode<T: AnchorDeserialize>(
+    expected_program_id: &Pubkey,
+    program_id: &Pubkey,
+    data: &[u8],
+) -> std::result::Result<T, ReturnDataError> {
+    if expected_program_id != program_id {
+        return Err(ReturnDataError::ProgramMismatch {
+            expected: *expected_program_id,
+            actual: *program_id,
+        });
+    }
+    if data.len() > MAX_RETURN_DATA {
+        return Err(ReturnDataError::TooLarge);
+    }
+    let mut padded = [0u8; MAX_RETURN_DATA];
+    padded[..data.len()].copy_from_slice(data);
+    Ok(T::deserialize(&mut &padded[..])?)
+}
+
+/// Decodes base64-encoded return data, as returned by `simulateTransaction`.
+pub fn decode_base64<T: AnchorDeserialize>(
+    expected_program_id: &Pubkey,
+    program_id: &Pubkey,
+    data: &str,
+) -> std::result::Result<T, ReturnDataError> {
+    decode(expected_program_id, program_id, &base64::decode(data)?)
+}
+
+/// Decodes the return data of a transaction from its logs.
+///
+/// Only the last `Program return:` line is considered, as each instruction
+/// which sets return data overwrites that of the previous one.
+pub fn decode_from_logs<T: AnchorDeserialize>(
+    expected_program_id: &Pubkey,
+    logs: &[String],
+) -> std::result::Result<T, ReturnDataError> {
+    let line = logs
+        .iter()
+        .rev()
+        .find_map(|log| log.strip_prefix(PROGRAM_RETURN_LOG_PREFIX))
+        .ok_or(ReturnDataError::NotFound)?;
+    let (program_id, data) = line
+        .split_once(' ')
+        .ok_or_else(|| ReturnDataError::MalformedLog(line.to_string()))?;
+    let program_id: Pubkey = program_id
+        .parse()
+        .map_err(|_| ReturnDataError::MalformedLog(line.to_string()))?;
+    decode_base64(expected_program_id, &program_id, data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(AnchorSerialize, AnchorDeserialize, Debug, PartialEq, Eq)]
+    struct Quote {
+        amount: u64,
+        dust: u64,
+    }
+
+    #[test]
+    fn test_decode_truncated() {
+        let quote = Quote {
+            amount: 1_000,
+            dust: 0,
+        };
+        let mut data = quote.try_to_vec().unwrap();
+        while data.last() == Some(&0) {
+            data.pop();
+        }
+        let program_id = Pubkey::new_unique();
+        assert_eq!(
+            decode::<Quote>(&program_id, &program_id, &data).unwrap(),
+            quote
+        );
+    }
+
+    #[test]
+    fn test_decode_from_logs() {
+        let program_id = Pubkey::new_unique();
+        let first = base64::encode(Quote { amount: 1, dust: 2 }.try_to_vec().unwrap());
+        let last = base64::encode(Quote { amount: 3, dust: 4 }.try_to_vec().unwrap());
+        let logs = vec![
+            format!("Program {} invoke [1]", program_id),
+            format!("{}{} {}", PROGRAM_RETURN_LOG_PREFIX, program_id, first),
+            format!("{}{} {}", PROGRAM_RETURN_LOG_PREFIX, program_id, last),
+            format!("Program {} success", program_id),
+        ];
+        assert_eq!(
+            decode_from_logs::<Quote>(&program_id, &logs).unwrap(),
+            Quote { amount: 3, dust: 4 }
+        );
+        assert!(matches!(
+            decode_from_logs::<Quote>(&Pubkey::new_unique(), &logs),
+            Err(ReturnDataError::ProgramMismatch { .. })
+        ));
+        assert!(matches!(
+            decode_from_logs::<Quote>(&program_id, &[]),
+            Err(ReturnDataError::NotFound)
+        ));
+    }
+}
@@ -0,0 +1,84 @@
+//! Deposits into and withdraws from a wrapper, checking that the conversions
+//! never panic and never round in the user's favour.
+#![no_main]
+
+use add_decimals::WrappedToken;
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+
+#[derive(Arbitrary, Debug)]
+enum Op {
+    Deposit { amount: u64 },
+    Withdraw { max_burn_amount: u64 },
+}
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    added_decimals: u8,
+    ops: Vec<Op>,
+}
+
+fuzz_target!(|input: Input| {
+    // `initialize_wrapper` rejects multipliers which overflow.
+    let multiplier = match 10u64.checked_pow(input.added_decimals.into()) {
+        Some(multiplier) => multiplier,
+        None => return,
+    };
+    let mut wrapper = WrappedToken::default();
+    wrapper.decimals = input.added_decimals;
+    wrapper.multiplier = multiplier;
+
+    let mut vault_balance: u64 = 0;
+    let mut wrapped_balance: u64 = 0;
+    let mut total_deposited: u128 = 0;
+    let mut total_withdrawn: u128 = 0;
+
+    for op in input.ops {
+        match op {
+            Op::Deposit { amount } => {
+                let mint_amount = match wrapper.to_wrapped_amount(amount) {
+                    Some(mint_amount) => mint_amount,
+                    None => continue,
+                };
+                assert_eq!(wrapper.to_underlying_amount(mint_amount), Some(amount));
+                // The token program rejects deposits overflowing the balances.
+                let (vault, wrapped) = match (
+                    vault_balance.checked_add(amount),
+                    wrapped_balance.checked_add(mint_amount),
+                ) {
+                    (Some(vault), Some(wrapped)) => (vault, wrapped),
+                    _ => continue,
+                };
+                vault_balance = vault;
+                wrapped_balance = wrapped;
+                total_deposited += u128::from(amount);
+            }
+            Op::Withdraw { max_burn_amount } => {
+                if max_burn_amount == 0 || max_burn_amount > wrapped_balance {
+                    continue;
+                }
+                let quote = wrapper
+                    .quote_withdraw(max_burn_amount)
+                    .expect("quote_withdraw must not fail for a held balance");
+
+                // Only whole underlying tokens are withdrawn; the rest is dust.
+                assert_eq!(
+                    u128::from(quote.burn_amount),
+                    u128::from(quote.withdraw_amount) * u128::from(multiplier)
+                );
+                assert_eq!(quote.burn_amount + quote.dust_amount, max_burn_amount);
+                assert!(quote.dust_amount < multiplier);
+
+                vault_balance = vault_balance
+                    .checked_sub(quote.withdraw_amount)
+                    .expect("withdrew more underlying than the vault holds");
+                wrapped_balance -= quote.burn_amount;
+                total_withdrawn += u128::from(quote.withdraw_amount);
+            }
+        }
+
+        assert!(total_withdrawn <= total_deposited);
+        // Every wrapped token outstanding is backed by the vault.
+        assert!(u128::from(wrapped_balance) <= u128::from(vault_balance) * u128::from(multiplier));
+    }
+});
@@ -6,16 +6,19 @@
 #![deny(rustdoc::all)]
 #![allow(rustdoc::missing_doc_code_examples)]
 
+use anchor_lang::solana_program::program::set_return_data;
 use anchor_lang::{prelude::*, solana_program::pubkey::PUBKEY_BYTES};
 use anchor_spl::token::{Mint, Token, TokenAccount};
-use continuation_router::{ActionType, RouterActionProcessor};
+use continuation_router::ActionType;
 use vipers::prelude::*;
 use vipers::program_err;
 
 mod events;
+mod quote;
 mod transfer;
 
 pub use events::*;
+pub use quote::*;
 
 declare_id!("DecZY86MU5Gj7kppfUCEmd4LbXXuyZH1yHaP2NTqdiZB");
 
@@ -105,10 +108,11 @@ pub mod add_decimals {
         );
 
         // Compute true withdraw amount
-        let withdraw_amount =
-            unwrap_int!(ctx.accounts.wrapper.to_underlying_amount(max_burn_amount),);
-        let burn_amount = unwrap_int!(ctx.accounts.wrapper.to_wrapped_amount(withdraw_amount),);
-        let dust_amount = unwrap_int!(max_burn_amount.checked_sub(burn_amount));
+        let WithdrawQuote {
+            withdraw_amount,
+            burn_amount,
+            dust_amount,
+        } = unwrap_int!(ctx.accounts.wrapper.quote_withdraw(max_burn_amount));
 
         // Burn wrapped and withdraw underlying
         ctx.accounts.burn_wrapped(burn_amount)?;
@@ -131,23 +135,43 @@ pub mod add_decimals {
         withdraw(ctx, max_burn_amount)
     }
 
-    #[state]
-    pub struct AddDecimals;
-
-    impl<'info> RouterActionProcessor<'info, UserStake<'info>> for AddDecimals {
-        fn process_action(
-            ctx: Context<UserStake>,
-            action: u16,
-            amount_in: u64,
-            _minimum_amount_out: u64,
-        ) -> Result<()> {
-            let action_type = try_or_err!(ActionType::try_from(action), UnknownAction);
-            msg!("Router action received: {:?}", action_type);
-            match action_type {
-                ActionType::ADWithdraw => withdraw(ctx, amount_in),
-                ActionType::ADDeposit => deposit(ctx, amount_in),
-                _ => program_err!(UnknownAction),
+    /// Quotes the wrapped tokens minted by a [add_decimals::deposit].
+    ///
+    /// The Borsh-encoded [DepositQuote] is returned via return data.
+    pub fn quote_deposit(ctx: Context<QuoteWrapper>, deposit_amount: u64) -> Result<()> {
+        let mint_amount = unwrap_int!(ctx.accounts.wrapper.to_wrapped_amount(deposit_amount));
+        set_return_data(
+            &DepositQuote {
+                deposit_amount,
+                mint_amount,
             }
+            .try_to_vec()?,
+        );
+        Ok(())
+    }
+
+    /// Quotes the underlying tokens withdrawn by a [add_decimals::withdraw].
+    ///
+    /// The Borsh-encoded [WithdrawQuote] is returned via return data.
+    pub fn quote_withdraw(ctx: Context<QuoteWrapper>, max_burn_amount: u64) -> Result<()> {
+        let quote = unwrap_int!(ctx.accounts.wrapper.quote_withdraw(max_burn_amount));
+        set_return_data(&quote.try_to_vec()?);
+        Ok(())
+    }
+
+    /// Processes an action routed through the [continuation_router].
+    pub fn process_router_action(
+        ctx: Context<UserStake>,
+        action: u16,
+        amount_in: u64,
+        _minimum_amount_out: u64,
+    ) -> Result<()> {
+        let action_type = try_or_err!(ActionType::try_from(action), UnknownAction);
+        msg!("Router action received: {:?}", action_type);
+        match action_type {
+            ActionType::ADWithdraw => withdraw(ctx, amount_in),
+            ActionType::ADDeposit => deposit(ctx, amount_in),
+            _ => program_err!(UnknownAction),
         }
     }
 }
@@ -278,6 +302,13 @@ impl<'info> Validate<'info> for UserStake<'info> {
     }
 }
 
+/// Accounts for quoting conversions of the wrapper.
+#[derive(Accounts)]
+pub struct QuoteWrapper<'info> {
+    /// Wrapper account.
+    pub wrapper: Account<'info, WrappedToken>,
+}
+
 /// Contains the info of a wrapped token. Immutable.
 ///
 /// There are two tokens here:
@@ -313,6 +344,20 @@ impl WrappedToken {
         amount.checked_div(self.multiplier)
     }
 
+    /// Computes the amounts involved in burning up to `max_burn_amount` wrapped tokens.
+    ///
+    /// Only multiples of the multiplier can be burned; the remainder is left as dust.
+    pub fn quote_withdraw(&self, max_burn_amount: u64) -> Option<WithdrawQuote> {
+        let withdraw_amount = self.to_underlying_amount(max_burn_amount)?;
+        let burn_amount = self.to_wrapped_amount(withdraw_amount)?;
+        let dust_amount = max_burn_amount.checked_sub(burn_amount)?;
+        Some(WithdrawQuote {
+            withdraw_amount,
+            burn_amount,
+            dust_amount,
+        })
+    }
+
     /// Gets the nonce.
     pub fn nonce(&self) -> u8 {
         self.__nonce
@@ -0,0 +1,23 @@
+//! Conversion quotes returned by the view instructions.
+
+use anchor_lang::prelude::*;
+
+/// Return data of [crate::add_decimals::quote_deposit].
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DepositQuote {
+    /// Underlying tokens deposited.
+    pub deposit_amount: u64,
+    /// Wrapped tokens minted.
+    pub mint_amount: u64,
+}
+
+/// Return data of [crate::add_decimals::quote_withdraw].
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WithdrawQuote {
+    /// Underlying tokens withdrawn.
+    pub withdraw_amount: u64,
+    /// Wrapped tokens burned.
+    pub burn_amount: u64,
+    /// Wrapped tokens remaining as dust.
+    pub dust_amount: u64,
+}
@@ -16,4 +16,50 @@ pub trait ProcessAction<'info>: Sized {
     fn input_account(&self) -> &Account<'info, TokenAccount>;
 
     fn output_account(&self) -> &Account<'info, TokenAccount>;
+
+    fn secondary_output_account(&self) -> Option<&Account<'info, TokenAccount>> {
+        None
+    }
+}
+
+/// Name of the global instruction which pass-through programs implement in order
+/// to be routed through. It takes the same arguments as
+/// [crate::RouterActionProcessor::process_action].
+pub const PROCESS_ROUTER_ACTION_IX: &str = "process_router_action";
+
+/// Invokes [PROCESS_ROUTER_ACTION_IX] on a pass-through program.
+///
+/// This replaces the `#[interface]` CPI, which is only implementable via the
+/// deprecated `#[state]` attribute, for actions declared with
+/// `#[router_action(pass_through_v2)]`.
+pub fn invoke_process_router_action<'info>(
+    program: &AccountInfo<'info>,
+    accounts: &[AccountInfo<'info>],
+    action: u16,
+    amount_in: u64,
+    minimum_amount_out: u64,
+) -> Result<()> {
+    let preimage = format!("global:{}", PROCESS_ROUTER_ACTION_IX);
+    let mut data =
+        anchor_lang::solana_program::hash::hash(preimage.as_bytes()).to_bytes()[..8].to_vec();
+    data.extend((action, amount_in, minimum_amount_out).try_to_vec()?);
+
+    let ix = anchor_lang::solana_program::instruction::Instruction {
+        program_id: *program.key,
+        accounts: accounts
+            .iter()
+            .map(|acc| {
+                if acc.is_writable {
+                    AccountMeta::new(*acc.key, acc.is_signer)
+                } else {
+                    AccountMeta::new_readonly(*acc.key, acc.is_signer)
+                }
+            })
+            .collect(),
+        data,
+    };
+    let mut infos = accounts.to_vec();
+    infos.push(program.clone());
+    anchor_lang::solana_program::program::invoke(&ix, &infos)?;
+    Ok(())
 }
@@ -4,6 +4,7 @@ use std::ops::Deref;
 
 use crate::action::ProcessAction;
 use crate::*;
+use anchor_lang::solana_program::program_pack::Pack;
 
 macro_rules! build_swap_context {
     ($component:expr, $ctx:expr $(,)?) => {{
@@ -30,6 +31,146 @@ impl<'info> Deref for SSDepositB<'info> {
     }
 }
 
+impl<'info> Deref for SSZapA<'info> {
+    type Target = SSZap<'info>;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<'info> Deref for SSZapB<'info> {
+    type Target = SSZap<'info>;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+/// Computes the amount of `amount_in` to swap into the other token of a pool such
+/// that the rest of the input and the swap output are in the ratio of the reserves
+/// after the swap, which maximizes the LP tokens received from depositing both.
+///
+/// Solves `(amount_in - x) / x = (reserve_in + x) / (reserve_out - x)`, assuming the
+/// pool prices both tokens 1:1 and ignoring fees. StableSwap pools approximate this
+/// near balance, and any remaining imbalance is small.
+pub fn zap_swap_amount(amount_in: u64, reserve_in: u64, reserve_out: u64) -> Option<u64> {
+    let denominator = u128::from(amount_in)
+        .checked_add(reserve_in.into())?
+        .checked_add(reserve_out.into())?;
+    if denominator == 0 {
+        return Some(0);
+    }
+    u128::from(amount_in)
+        .checked_mul(reserve_out.into())?
+        .checked_div(denominator)?
+        .try_into()
+        .ok()
+}
+
+/// Reads the amount of tokens in a reserve.
+fn reserve_amount(reserve: &AccountInfo) -> Result<u64> {
+    let data = reserve.try_borrow_data()?;
+    let account = spl_token::state::Account::unpack(&data)?;
+    Ok(account.amount)
+}
+
+/// Runs the zap component instructions.
+fn process_zap<'info, T: Deref<Target = SSZap<'info>>>(
+    ctx: &ActionContext<'_, '_, '_, 'info, T>,
+    amount_in: u64,
+    minimum_amount_out: u64,
+    input_is_a: bool,
+) -> Result<()> {
+    let zap: &SSZap<'info> = ctx.action;
+    let other = &zap.other.user_token.user;
+    assert_keys_eq!(other.owner, ctx.owner.key(), OutputOwnerMismatch);
+
+    let swap_amount = unwrap_int!(zap_swap_amount(
+        amount_in,
+        reserve_amount(&zap.input.reserve)?,
+        reserve_amount(&zap.other.user_token.reserve)?,
+    ));
+    let other_amount = if swap_amount > 0 {
+        let initial_balance = other.amount;
+        let cpi_accounts = stable_swap_anchor::Swap {
+            user: build_swap_context!(zap, ctx),
+            input: (&zap.input).into(),
+            output: (&zap.other).into(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.swap_program.clone(), cpi_accounts);
+        stable_swap_anchor::swap(cpi_ctx, swap_amount, 0)?;
+
+        let other = &mut other.clone();
+        other.reload()?;
+        unwrap_int!(other.amount.checked_sub(initial_balance))
+    } else {
+        0
+    };
+    let input_amount = unwrap_int!(amount_in.checked_sub(swap_amount));
+
+    let (input_a, input_b, amount_a, amount_b) = if input_is_a {
+        (
+            &zap.input,
+            &zap.other.user_token,
+            input_amount,
+            other_amount,
+        )
+    } else {
+        (
+            &zap.other.user_token,
+            &zap.input,
+            other_amount,
+            input_amount,
+        )
+    };
+    let cpi_accounts = stable_swap_anchor::Deposit {
+        user: build_swap_context!(zap, ctx),
+        input_a: input_a.into(),
+        input_b: input_b.into(),
+        output_lp: zap.output_lp.to_account_info(),
+        pool_mint: zap.pool_mint.clone(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.swap_program.clone(), cpi_accounts);
+    stable_swap_anchor::deposit(cpi_ctx, amount_a, amount_b, minimum_amount_out)
+}
+
+impl<'info> ProcessAction<'info> for SSZapA<'info> {
+    /// Runs the zap component instructions.
+    fn process(
+        ctx: &ActionContext<'_, '_, '_, 'info, Self>,
+        amount_in: u64,
+        minimum_amount_out: u64,
+    ) -> Result<()> {
+        process_zap(ctx, amount_in, minimum_amount_out, true)
+    }
+
+    fn input_account(&self) -> &Account<'info, TokenAccount> {
+        &self.input.user
+    }
+
+    fn output_account(&self) -> &Account<'info, TokenAccount> {
+        &self.output_lp
+    }
+}
+
+impl<'info> ProcessAction<'info> for SSZapB<'info> {
+    /// Runs the zap component instructions.
+    fn process(
+        ctx: &ActionContext<'_, '_, '_, 'info, Self>,
+        amount_in: u64,
+        minimum_amount_out: u64,
+    ) -> Result<()> {
+        process_zap(ctx, amount_in, minimum_amount_out, false)
+    }
+
+    fn input_account(&self) -> &Account<'info, TokenAccount> {
+        &self.input.user
+    }
+
+    fn output_account(&self) -> &Account<'info, TokenAccount> {
+        &self.output_lp
+    }
+}
+
 impl<'info> ProcessAction<'info> for SSDepositA<'info> {
     /// Runs the deposit component instruction.
     fn process(
@@ -114,6 +255,38 @@ impl<'info> ProcessAction<'info> for SSWithdrawOne<'info> {
     }
 }
 
+impl<'info> ProcessAction<'info> for SSWithdraw<'info> {
+    /// Runs the withdraw component instruction.
+    fn process(
+        ctx: &ActionContext<'_, '_, '_, 'info, Self>,
+        amount_in: u64,
+        minimum_amount_out: u64,
+    ) -> Result<()> {
+        let action = ctx.action;
+        let cpi_accounts = stable_swap_anchor::Withdraw {
+            user: build_swap_context!(action, ctx),
+            input_lp: action.input_lp.to_account_info(),
+            pool_mint: action.pool_mint.clone(),
+            output_a: (&action.output_a).into(),
+            output_b: (&action.output_b).into(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.swap_program.clone(), cpi_accounts);
+        stable_swap_anchor::withdraw(cpi_ctx, amount_in, minimum_amount_out, 0)
+    }
+
+    fn input_account(&self) -> &Account<'info, TokenAccount> {
+        &self.input_lp
+    }
+
+    fn output_account(&self) -> &Account<'info, TokenAccount> {
+        &self.output_a.user_token.user
+    }
+
+    fn secondary_output_account(&self) -> Option<&Account<'info, TokenAccount>> {
+        Some(&self.output_b.user_token.user)
+    }
+}
+
 impl<'info> ProcessAction<'info> for SSSwap<'info> {
     /// Runs the deposit component instruction.
     fn process(
@@ -157,3 +330,34 @@ impl<'info> From<&SwapOutput<'info>> for stable_swap_anchor::SwapOutput<'info> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zap_swap_amount_balanced() {
+        // swap x of 1_000 into a balanced pool of 1_000_000 each:
+        // (1_000 - x) / x = (1_000_000 + x) / (1_000_000 - x)
+        let x = zap_swap_amount(1_000, 1_000_000, 1_000_000).unwrap();
+        assert_eq!(x, 499);
+    }
+
+    #[test]
+    fn test_zap_swap_amount_imbalanced() {
+        // the pool is short of the other token, so most of the input is swapped
+        assert_eq!(zap_swap_amount(1_000, 9_000, 1_000), Some(90));
+        assert_eq!(zap_swap_amount(1_000, 1_000, 9_000), Some(818));
+        assert_eq!(zap_swap_amount(1_000, 0, 0), Some(0));
+        assert_eq!(zap_swap_amount(0, 1_000, 1_000), Some(0));
+        assert_eq!(zap_swap_amount(1_000, 1_000, 0), Some(0));
+    }
+
+    #[test]
+    fn test_zap_swap_amount_large() {
+        assert_eq!(
+            zap_swap_amount(u64::MAX, u64::MAX, u64::MAX),
+            Some(u64::MAX / 3)
+        );
+    }
+}
@@ -7,6 +7,7 @@
 
 use continuation_router_syn::router_action;
 
+use anchor_lang::solana_program::program::set_return_data;
 use anchor_lang::{prelude::*, solana_program::pubkey::PUBKEY_BYTES};
 use anchor_spl::token::{Token, TokenAccount};
 use num_enum::{IntoPrimitive, TryFromPrimitive};
@@ -112,9 +113,16 @@ pub mod continuation_router {
     }
 
     /// Cleans up the transaction and checks several invariants.
+    ///
+    /// The Borsh-encoded [RouteQuote] is returned via return data, so simulating
+    /// a route quotes it.
     pub fn end(ctx: Context<End>) -> Result<()> {
         let continuation = &ctx.accounts.continuation;
         require!(continuation.steps_left == 0, EndIncomplete);
+        require!(
+            continuation.secondary_amount_in.amount == 0,
+            SecondaryBalanceUnconsumed
+        );
 
         let result_balance = ctx.accounts.output.amount;
         require!(
@@ -137,10 +145,16 @@ pub mod continuation_router {
             MinimumOutNotMet,
         );
 
-        emit!(SwapCompleteEvent {
-            owner: continuation.owner,
+        let quote = RouteQuote {
             amount_in: continuation.initial_amount_in,
             amount_out: TokenAmount::new(continuation.minimum_amount_out.mint, amount_out),
+        };
+        set_return_data(&quote.try_to_vec()?);
+
+        emit!(SwapCompleteEvent {
+            owner: continuation.owner,
+            amount_in: quote.amount_in,
+            amount_out: quote.amount_out,
         });
         Ok(())
     }
@@ -155,6 +169,12 @@ pub mod continuation_router {
         process_action!(ctx)
     }
 
+    pub fn ss_withdraw<'info>(
+        ctx: Context<'_, '_, '_, 'info, SSWithdrawAccounts<'info>>,
+    ) -> Result<()> {
+        process_action!(ctx)
+    }
+
     pub fn ss_deposit_a<'info>(
         ctx: Context<'_, '_, '_, 'info, SSDepositAAccounts<'info>>,
     ) -> Result<()> {
@@ -167,6 +187,14 @@ pub mod continuation_router {
         process_action!(ctx)
     }
 
+    pub fn ss_zap_a<'info>(ctx: Context<'_, '_, '_, 'info, SSZapAAccounts<'info>>) -> Result<()> {
+        process_action!(ctx)
+    }
+
+    pub fn ss_zap_b<'info>(ctx: Context<'_, '_, '_, 'info, SSZapBAccounts<'info>>) -> Result<()> {
+        process_action!(ctx)
+    }
+
     pub fn ad_withdraw<'info>(
         ctx: Context<'_, '_, '_, 'info, ADWithdrawAccounts<'info>>,
     ) -> Result<()> {
@@ -178,6 +206,12 @@ pub mod continuation_router {
     ) -> Result<()> {
         process_action!(ctx)
     }
+
+    pub fn rd_redeem<'info>(
+        ctx: Context<'_, '_, '_, 'info, RDRedeemAccounts<'info>>,
+    ) -> Result<()> {
+        process_action!(ctx)
+    }
 }
 
 // --------------------------------
@@ -215,6 +249,26 @@ pub struct SSWithdrawOne<'info> {
     pub output: SwapOutput<'info>,
 }
 
+/// Withdraws both tokens of a pool. Token A becomes the next input of the route,
+/// while token B is held as the secondary balance of the [Continuation].
+#[router_action]
+#[derive(Accounts)]
+pub struct SSWithdraw<'info> {
+    /// Swap and authority
+    pub swap: StableSwap<'info>,
+    /// The pool mint of the swap.
+    /// CHECK: Checked by [stable_swap_anchor] program.
+    #[account(mut)]
+    pub pool_mint: AccountInfo<'info>,
+    /// The input account for LP tokens.
+    #[account(mut)]
+    pub input_lp: Account<'info, TokenAccount>,
+    /// The output of token A.
+    pub output_a: SwapOutput<'info>,
+    /// The output of token B.
+    pub output_b: SwapOutput<'info>,
+}
+
 #[router_action]
 #[derive(Accounts)]
 pub struct SSDepositA<'info> {
@@ -227,20 +281,41 @@ pub struct SSDepositB<'info> {
     pub inner: SSDeposit<'info>,
 }
 
-#[router_action(pass_through)]
+/// Zaps token A into LP tokens.
+#[router_action]
+#[derive(Accounts)]
+pub struct SSZapA<'info> {
+    pub inner: SSZap<'info>,
+}
+
+/// Zaps token B into LP tokens.
+#[router_action]
+#[derive(Accounts)]
+pub struct SSZapB<'info> {
+    pub inner: SSZap<'info>,
+}
+
+#[router_action(pass_through_v2)]
 #[derive(Accounts)]
 pub struct ADWithdraw<'info> {
     pub input: Account<'info, TokenAccount>,
     pub output: Account<'info, TokenAccount>,
 }
 
-#[router_action(pass_through)]
+#[router_action(pass_through_v2)]
 #[derive(Accounts)]
 pub struct ADDeposit<'info> {
     pub input: Account<'info, TokenAccount>,
     pub output: Account<'info, TokenAccount>,
 }
 
+#[router_action(pass_through_v2)]
+#[derive(Accounts)]
+pub struct RDRedeem<'info> {
+    pub input: Account<'info, TokenAccount>,
+    pub output: Account<'info, TokenAccount>,
+}
+
 // --------------------------------
 // Instructions
 // --------------------------------
@@ -378,6 +453,12 @@ pub struct SSWithdrawOneAccounts<'info> {
     pub action: SSWithdrawOne<'info>,
 }
 
+#[derive(Accounts)]
+pub struct SSWithdrawAccounts<'info> {
+    pub continuation: ContinuationAccounts<'info>,
+    pub action: SSWithdraw<'info>,
+}
+
 #[derive(Accounts)]
 pub struct SSDepositAAccounts<'info> {
     pub continuation: ContinuationAccounts<'info>,
@@ -390,6 +471,18 @@ pub struct SSDepositBAccounts<'info> {
     pub action: SSDepositB<'info>,
 }
 
+#[derive(Accounts)]
+pub struct SSZapAAccounts<'info> {
+    pub continuation: ContinuationAccounts<'info>,
+    pub action: SSZapA<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SSZapBAccounts<'info> {
+    pub continuation: ContinuationAccounts<'info>,
+    pub action: SSZapB<'info>,
+}
+
 #[derive(Accounts)]
 pub struct ADWithdrawAccounts<'info> {
     pub continuation: ContinuationAccounts<'info>,
@@ -402,6 +495,12 @@ pub struct ADDepositAccounts<'info> {
     pub action: ADDeposit<'info>,
 }
 
+#[derive(Accounts)]
+pub struct RDRedeemAccounts<'info> {
+    pub continuation: ContinuationAccounts<'info>,
+    pub action: RDRedeem<'info>,
+}
+
 // --------------------------------
 // Various accounts
 // --------------------------------
@@ -445,6 +544,25 @@ pub struct SSDeposit<'info> {
     pub output_lp: Account<'info, TokenAccount>,
 }
 
+/// Zap accounts: swaps part of the input into the other token of the pool,
+/// then deposits both.
+#[derive(Accounts)]
+pub struct SSZap<'info> {
+    /// Swap and authority
+    pub swap: StableSwap<'info>,
+    /// The input token of this component of the route.
+    pub input: SwapToken<'info>,
+    /// The other token of the pool, which part of the input is swapped into.
+    pub other: SwapOutput<'info>,
+    /// The pool mint of the swap.
+    /// CHECK: Checked by [stable_swap_anchor] program.
+    #[account(mut)]
+    pub pool_mint: AccountInfo<'info>,
+    /// The destination account for LP tokens.
+    #[account(mut)]
+    pub output_lp: Account<'info, TokenAccount>,
+}
+
 /// Accounts for interacting with a StableSwap pool.
 #[derive(Accounts)]
 pub struct StableSwap<'info> {
@@ -512,6 +630,13 @@ pub struct Continuation {
     /// The minimum amount of tokens to output at the end of the transaction.
     pub minimum_amount_out: TokenAmount,
 
+    /// Account holding the secondary balance, e.g. the second token of an [SSWithdraw].
+    pub secondary_input: Pubkey,
+
+    /// The secondary amount of tokens, which a subsequent step must merge
+    /// into the route before it ends.
+    pub secondary_amount_in: TokenAmount,
+
     /// Nonce field to the struct to hold the bump seed for the program derived address,
     /// sourced from `<https://github.com/project-serum/anchor/blob/ec6888a3b9f702bc41bd3266e7dd70116df3549c/lang/attribute/account/src/lib.rs#L220-L221.>`.
     __nonce: u8,
@@ -526,6 +651,8 @@ impl Continuation {
         + PUBKEY_BYTES
         + 8
         + TokenAmount::LEN
+        + PUBKEY_BYTES
+        + TokenAmount::LEN
         + 1;
 }
 
@@ -561,6 +688,12 @@ pub enum ErrorCode {
     MinimumOutNotMet,
     #[msg("Output mint does not match continuation output mint.")]
     OutputMintMismatch,
+    #[msg("A secondary balance is already held.")]
+    SecondaryBalanceInUse,
+    #[msg("Secondary balance must be merged with the primary balance.")]
+    SecondaryOutputMismatch,
+    #[msg("Secondary balance was not consumed.")]
+    SecondaryBalanceUnconsumed,
 }
 
 // --------------------------------
@@ -600,12 +733,25 @@ impl TokenAmount {
     }
 }
 
+/// Return data of [continuation_router::end].
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct RouteQuote {
+    /// The initial amount of tokens in.
+    pub amount_in: TokenAmount,
+    /// The amount of tokens received by the output account.
+    pub amount_out: TokenAmount,
+}
+
 /// An action.
 pub trait Action {
     const TYPE: ActionType;
 }
 
 /// Interface for programs that can be routed through.
+///
+/// Deprecated: implementing this requires `#[state]`. No action of the router
+/// invokes it anymore; actions invoke the global [action::PROCESS_ROUTER_ACTION_IX]
+/// instruction instead. It is only kept for `#[router_action(pass_through)]`.
 #[interface]
 pub trait RouterActionProcessor<'info, T: Accounts<'info>> {
     fn process_action(
@@ -626,7 +772,12 @@ pub enum ActionType {
     SSWithdrawOne = 1,
     SSDepositA = 2,
     SSDepositB = 3,
+    SSWithdraw = 4,
+    SSZapA = 5,
+    SSZapB = 6,
 
     ADWithdraw = 10,
     ADDeposit = 11,
+
+    RDRedeem = 20,
 }
@@ -1,12 +1,18 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Token, TokenAccount};
-use vipers::{assert_keys_eq, invariant};
+use vipers::{assert_keys_eq, assert_keys_neq, invariant, unwrap_int};
 
 use crate::{Action, Continuation, SwapActionEvent, TokenAmount};
 
 pub trait ActionInputOutput<'info>: Action {
     fn input_account(&self) -> &Account<'info, TokenAccount>;
     fn output_account(&self) -> &Account<'info, TokenAccount>;
+
+    /// Account receiving a second output of the action, which the continuation
+    /// holds as its secondary balance.
+    fn secondary_output_account(&self) -> Option<&Account<'info, TokenAccount>> {
+        None
+    }
 }
 
 pub struct ActionContext<'a, 'b, 'c, 'info, T> {
@@ -37,20 +43,43 @@ pub trait Processor<'info>: ActionInputOutput<'info> {
         invariant!(continuation.steps_left > 0, NoMoreSteps);
 
         let input_account = self.input_account();
-        assert_keys_eq!(
-            input_account.key(),
-            continuation.input,
-            PathInputOutputMismatch
-        );
+        let output_account = self.output_account();
+
+        // A pending secondary balance is merged into the output of a step which
+        // either consumes it as input and outputs to the primary account,
+        // or consumes the primary balance and outputs to the secondary account.
+        let has_secondary = continuation.secondary_amount_in.amount != 0;
+        let (amount_in, carried) =
+            if has_secondary && input_account.key() == continuation.secondary_input {
+                assert_keys_eq!(
+                    output_account.key(),
+                    continuation.input,
+                    SecondaryOutputMismatch
+                );
+                (
+                    continuation.secondary_amount_in,
+                    Some(continuation.amount_in),
+                )
+            } else {
+                assert_keys_eq!(
+                    input_account.key(),
+                    continuation.input,
+                    PathInputOutputMismatch
+                );
+                if has_secondary && output_account.key() == continuation.secondary_input {
+                    (
+                        continuation.amount_in,
+                        Some(continuation.secondary_amount_in),
+                    )
+                } else {
+                    (continuation.amount_in, None)
+                }
+            };
+        let carried_amount = carried.map(|carried| carried.amount).unwrap_or(0);
         assert_keys_eq!(input_account.owner, continuation.owner, InputOwnerMismatch);
-        assert_keys_eq!(
-            input_account.mint,
-            continuation.amount_in.mint,
-            InputMintMismatch
-        );
+        assert_keys_eq!(input_account.mint, amount_in.mint, InputMintMismatch);
 
         // ensure swap is non-zero
-        let amount_in = continuation.amount_in;
         invariant!(amount_in.amount != 0, ZeroSwap);
 
         // ensure amount in is at least the desired amount
@@ -60,13 +89,28 @@ pub trait Processor<'info>: ActionInputOutput<'info> {
         );
 
         // ensure output account is owned by the owner
-        let output_account = self.output_account();
         assert_keys_eq!(
             output_account.owner,
             continuation.owner,
             OutputOwnerMismatch
         );
 
+        // only one secondary balance may be held at a time
+        let secondary_output_account = self.secondary_output_account();
+        if let Some(secondary_output_account) = secondary_output_account {
+            invariant!(!has_secondary, SecondaryBalanceInUse);
+            assert_keys_eq!(
+                secondary_output_account.owner,
+                continuation.owner,
+                OutputOwnerMismatch
+            );
+            assert_keys_neq!(
+                secondary_output_account.key(),
+                output_account.key(),
+                SecondaryOutputMismatch
+            );
+        }
+
         // process step
         let initial_balance = output_account.amount;
         let minimum_amount_out = if continuation.steps_left == 1 {
@@ -75,10 +119,14 @@ pub trait Processor<'info>: ActionInputOutput<'info> {
                 output_account.mint,
                 OutputMintMismatch
             );
-            continuation.minimum_amount_out.amount
+            continuation
+                .minimum_amount_out
+                .amount
+                .saturating_sub(carried_amount)
         } else {
             0
         };
+        let secondary_initial_balance = secondary_output_account.map(|account| account.amount);
         self.process_unchecked(amount_in.amount, minimum_amount_out)?;
         let output_account = &mut output_account.clone();
         output_account.reload()?;
@@ -86,11 +134,32 @@ pub trait Processor<'info>: ActionInputOutput<'info> {
 
         // ensure that the new balance is higher than the old balance
         invariant!(result_balance >= initial_balance, BalanceLower);
-        let next_amount_in = result_balance - initial_balance;
+        let next_amount_in =
+            unwrap_int!((result_balance - initial_balance).checked_add(carried_amount));
 
         // write results
         continuation.input = output_account.key();
         continuation.amount_in = TokenAmount::new(output_account.mint, next_amount_in);
+        if carried.is_some() {
+            continuation.secondary_input = Pubkey::default();
+            continuation.secondary_amount_in = TokenAmount::default();
+        }
+        if let (Some(secondary_output_account), Some(secondary_initial_balance)) =
+            (secondary_output_account, secondary_initial_balance)
+        {
+            let secondary_output_account = &mut secondary_output_account.clone();
+            secondary_output_account.reload()?;
+            let secondary_result_balance = secondary_output_account.amount;
+            invariant!(
+                secondary_result_balance >= secondary_initial_balance,
+                BalanceLower
+            );
+            continuation.secondary_input = secondary_output_account.key();
+            continuation.secondary_amount_in = TokenAmount::new(
+                secondary_output_account.mint,
+                secondary_result_balance - secondary_initial_balance,
+            );
+        }
         continuation.steps_left -= 1;
 
         emit!(SwapActionEvent {
@@ -103,3 +172,761 @@ pub trait Processor<'info>: ActionInputOutput<'info> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::cell::{Cell, RefCell};
+    use std::collections::BTreeMap;
+
+    use anchor_lang::error::Error;
+    use anchor_lang::solana_program::program_pack::Pack;
+    use anchor_lang::solana_program::program_stubs::{set_syscall_stubs, SyscallStubs};
+    use anchor_lang::Discriminator;
+    use proptest::prelude::*;
+    use spl_token::state::{Account as SplAccount, AccountState};
+
+    use super::*;
+    use crate::{
+        continuation_router, ActionType, BeginV2, End, ErrorCode, RouteQuote, SwapCompleteEvent,
+    };
+
+    const NUM_MINTS: usize = 4;
+    const MAX_STEPS: usize = 6;
+    const RESERVE_AMOUNT: u64 = 1_000_000_000_000_000;
+
+    thread_local! {
+        static LOGGED_DATA: RefCell<Vec<Vec<u8>>> = const { RefCell::new(vec![]) };
+        static RETURN_DATA: RefCell<Option<Vec<u8>>> = const { RefCell::new(None) };
+    }
+
+    /// Captures the events and return data of the instructions under test.
+    struct TestSyscallStubs;
+
+    impl SyscallStubs for TestSyscallStubs {
+        fn sol_log(&self, _message: &str) {}
+
+        fn sol_log_data(&self, fields: &[&[u8]]) {
+            LOGGED_DATA.with(|logged| {
+                logged
+                    .borrow_mut()
+                    .extend(fields.iter().map(|field| field.to_vec()))
+            });
+        }
+
+        fn sol_set_return_data(&self, data: &[u8]) {
+            RETURN_DATA.with(|return_data| *return_data.borrow_mut() = Some(data.to_vec()));
+        }
+    }
+
+    fn install_syscall_stubs() {
+        static INSTALL: std::sync::Once = std::sync::Once::new();
+        INSTALL.call_once(|| {
+            set_syscall_stubs(Box::new(TestSyscallStubs));
+        });
+    }
+
+    fn swap_complete_events() -> Vec<SwapCompleteEvent> {
+        LOGGED_DATA.with(|logged| {
+            logged
+                .borrow()
+                .iter()
+                .filter(|data| data[..8] == SwapCompleteEvent::discriminator())
+                .map(|data| SwapCompleteEvent::try_from_slice(&data[8..]).unwrap())
+                .collect()
+        })
+    }
+
+    fn error_code(err: &Error) -> Option<u32> {
+        match err {
+            Error::AnchorError(err) => Some(err.error_code_number),
+            _ => None,
+        }
+    }
+
+    fn account_info(
+        key: Pubkey,
+        owner: Pubkey,
+        data: Vec<u8>,
+        is_signer: bool,
+    ) -> AccountInfo<'static> {
+        AccountInfo::new(
+            Box::leak(Box::new(key)),
+            is_signer,
+            true,
+            Box::leak(Box::new(0)),
+            Box::leak(data.into_boxed_slice()),
+            Box::leak(Box::new(owner)),
+            false,
+            0,
+        )
+    }
+
+    fn token_account(mint: Pubkey, owner: Pubkey, amount: u64) -> AccountInfo<'static> {
+        let mut data = vec![0; SplAccount::LEN];
+        SplAccount::pack(
+            SplAccount {
+                mint,
+                owner,
+                amount,
+                state: AccountState::Initialized,
+                ..SplAccount::default()
+            },
+            &mut data,
+        )
+        .unwrap();
+        account_info(Pubkey::new_unique(), spl_token::ID, data, false)
+    }
+
+    fn balance(info: &AccountInfo) -> u64 {
+        SplAccount::unpack(&info.try_borrow_data().unwrap())
+            .unwrap()
+            .amount
+    }
+
+    fn set_balance(info: &AccountInfo, amount: u64) {
+        let mut data = info.try_borrow_mut_data().unwrap();
+        let mut account = SplAccount::unpack(&data).unwrap();
+        account.amount = amount;
+        SplAccount::pack(account, &mut data).unwrap();
+    }
+
+    fn move_tokens(from: &AccountInfo, to: &AccountInfo, amount: u64) {
+        set_balance(from, balance(from).checked_sub(amount).unwrap());
+        set_balance(to, balance(to).checked_add(amount).unwrap());
+    }
+
+    /// A pool swapping between two mints at a constant rate.
+    struct MockPool {
+        mints: [Pubkey; 2],
+        reserves: [AccountInfo<'static>; 2],
+        /// Output tokens per input token, as a fraction.
+        rate: (u64, u64),
+    }
+
+    /// A step of a route through a [MockPool].
+    struct MockStep<'a> {
+        input: Account<'static, TokenAccount>,
+        output: Account<'static, TokenAccount>,
+        pool: &'a MockPool,
+        /// Second output of a withdrawal, and the reserve it is paid from.
+        secondary: Option<(Account<'static, TokenAccount>, AccountInfo<'static>)>,
+        /// Minimum amount out the pool was invoked with.
+        minimum_amount_out: Cell<Option<u64>>,
+    }
+
+    impl<'a> MockStep<'a> {
+        fn new(
+            input: &AccountInfo<'static>,
+            output: &AccountInfo<'static>,
+            pool: &'a MockPool,
+        ) -> Self {
+            MockStep {
+                input: Account::try_from(input).unwrap(),
+                output: Account::try_from(output).unwrap(),
+                pool,
+                secondary: None,
+                minimum_amount_out: Cell::new(None),
+            }
+        }
+
+        /// Makes the step also pay out to `secondary`, as a withdrawal of both tokens does.
+        fn with_secondary(
+            mut self,
+            secondary: &AccountInfo<'static>,
+            reserve: AccountInfo<'static>,
+        ) -> Self {
+            self.secondary = Some((Account::try_from(secondary).unwrap(), reserve));
+            self
+        }
+
+        fn swap(&self, amount_in: u64, minimum_amount_out: u64) -> Result<()> {
+            self.minimum_amount_out.set(Some(minimum_amount_out));
+            let side = if self.input.mint == self.pool.mints[0] {
+                0
+            } else {
+                1
+            };
+            let reserve_in = &self.pool.reserves[side];
+            let reserve_out = &self.pool.reserves[1 - side];
+
+            let (numerator, denominator) = self.pool.rate;
+            let amount_out = (amount_in as u128 * numerator as u128 / denominator as u128)
+                .min(balance(reserve_out) as u128) as u64;
+            require!(amount_out >= minimum_amount_out, MinimumOutNotMet);
+
+            move_tokens(&self.input.to_account_info(), reserve_in, amount_in);
+            move_tokens(reserve_out, &self.output.to_account_info(), amount_out);
+            if let Some((secondary, reserve)) = &self.secondary {
+                move_tokens(reserve, &secondary.to_account_info(), amount_out);
+            }
+            Ok(())
+        }
+    }
+
+    macro_rules! mock_actions {
+        ($($mock:ident => $action_type:ident),* $(,)?) => {
+            $(
+                struct $mock<'a, 'b>(&'a MockStep<'b>);
+
+                impl Action for $mock<'_, '_> {
+                    const TYPE: ActionType = ActionType::$action_type;
+                }
+
+                impl ActionInputOutput<'static> for $mock<'_, '_> {
+                    fn input_account(&self) -> &Account<'static, TokenAccount> {
+                        &self.0.input
+                    }
+
+                    fn output_account(&self) -> &Account<'static, TokenAccount> {
+                        &self.0.output
+                    }
+
+                    fn secondary_output_account(&self) -> Option<&Account<'static, TokenAccount>> {
+                        self.0.secondary.as_ref().map(|(secondary, _)| secondary)
+                    }
+                }
+
+                impl Processor<'static> for $mock<'_, '_> {
+                    fn process_unchecked(&self, amount_in: u64, minimum_amount_out: u64) -> Result<()> {
+                        self.0.swap(amount_in, minimum_amount_out)
+                    }
+                }
+            )*
+
+            const MOCK_ACTION_TYPES: &[ActionType] = &[$(ActionType::$action_type),*];
+
+            fn process_step(
+                action_type: ActionType,
+                step: &MockStep<'_>,
+                continuation: &mut Account<'static, Continuation>,
+            ) -> Result<()> {
+                match action_type {
+                    $(ActionType::$action_type => $mock(step).process(continuation),)*
+                    _ => unreachable!(),
+                }
+            }
+        };
+    }
+
+    mock_actions!(
+        MockSSSwap => SSSwap,
+        MockSSWithdrawOne => SSWithdrawOne,
+        MockSSWithdraw => SSWithdraw,
+        MockSSDepositA => SSDepositA,
+        MockSSDepositB => SSDepositB,
+        MockADWithdraw => ADWithdraw,
+        MockADDeposit => ADDeposit,
+        MockRDRedeem => RDRedeem,
+    );
+
+    #[derive(Clone, Debug)]
+    struct RouteParams {
+        /// Mints visited by the route.
+        path: Vec<usize>,
+        action_types: Vec<ActionType>,
+        /// Rate of the pool of each pair of mints.
+        rates: Vec<(u64, u64)>,
+        /// Balances held by the owner before the route.
+        balances: Vec<u64>,
+        amount_in: u64,
+        minimum_amount_out: u64,
+    }
+
+    prop_compose! {
+        fn route_path()
+            (start in 0..NUM_MINTS, hops in prop::collection::vec(1..NUM_MINTS, 1..=MAX_STEPS))
+            -> Vec<usize> {
+            let mut path = vec![start];
+            for hop in hops {
+                path.push((path.last().unwrap() + hop) % NUM_MINTS);
+            }
+            path
+        }
+    }
+
+    prop_compose! {
+        fn route_params()
+            (
+                path in route_path(),
+                action_types in prop::collection::vec(
+                    prop::sample::select(MOCK_ACTION_TYPES), MAX_STEPS
+                ),
+                rates in prop::collection::vec((1..=100u64, 1..=100u64), NUM_MINTS * NUM_MINTS),
+                balances in prop::collection::vec(0..1_000_000_000u64, NUM_MINTS),
+                amount_in in 1..1_000_000_000_000u64,
+                minimum_amount_out in prop_oneof![Just(0), 1..1_000_000_000_000_000u64],
+            ) -> RouteParams {
+            RouteParams {
+                path,
+                action_types,
+                rates,
+                balances,
+                amount_in,
+                minimum_amount_out,
+            }
+        }
+    }
+
+    fn pool_key(a: usize, b: usize) -> (usize, usize) {
+        (a.min(b), a.max(b))
+    }
+
+    fn begin(
+        owner_info: &AccountInfo<'static>,
+        input: &AccountInfo<'static>,
+        output: &AccountInfo<'static>,
+        amount_in: u64,
+        minimum_amount_out: u64,
+        num_steps: usize,
+    ) -> Account<'static, Continuation> {
+        let continuation_info = account_info(
+            Pubkey::new_unique(),
+            crate::ID,
+            vec![0; 8 + Continuation::LEN],
+            false,
+        );
+        let mut begin = BeginV2 {
+            continuation: Box::new(Account::try_from_unchecked(&continuation_info).unwrap()),
+            input: Box::new(Account::try_from(input).unwrap()),
+            output: Box::new(Account::try_from(output).unwrap()),
+            owner: Signer::try_from(owner_info).unwrap(),
+        };
+        continuation_router::begin_v2(
+            Context::new(&crate::ID, &mut begin, &[], BTreeMap::new()),
+            amount_in,
+            minimum_amount_out,
+            num_steps as u16,
+        )
+        .unwrap();
+        *begin.continuation
+    }
+
+    fn end(
+        continuation: &Account<'static, Continuation>,
+        output: &AccountInfo<'static>,
+        owner_info: &AccountInfo<'static>,
+    ) -> Result<()> {
+        let mut end = End {
+            continuation: Box::new(continuation.clone()),
+            output: Box::new(Account::try_from(output).unwrap()),
+            owner: Signer::try_from(owner_info).unwrap(),
+            payer: UncheckedAccount::try_from(owner_info.clone()),
+        };
+        continuation_router::end(Context::new(&crate::ID, &mut end, &[], BTreeMap::new()))
+    }
+
+    fn run_route(params: RouteParams) -> std::result::Result<(), TestCaseError> {
+        install_syscall_stubs();
+        LOGGED_DATA.with(|logged| logged.borrow_mut().clear());
+        RETURN_DATA.with(|return_data| *return_data.borrow_mut() = None);
+
+        let RouteParams {
+            path,
+            action_types,
+            rates,
+            balances,
+            amount_in,
+            minimum_amount_out,
+        } = params;
+        let num_steps = path.len() - 1;
+
+        let owner = Pubkey::new_unique();
+        let owner_info = account_info(owner, Pubkey::default(), vec![], true);
+        let mints: Vec<Pubkey> = (0..NUM_MINTS).map(|_| Pubkey::new_unique()).collect();
+        let accounts: Vec<AccountInfo<'static>> = mints
+            .iter()
+            .zip(&balances)
+            .map(|(mint, balance)| token_account(*mint, owner, *balance))
+            .collect();
+        let input = &accounts[path[0]];
+        let output = &accounts[path[num_steps]];
+        set_balance(input, balance(input) + amount_in);
+
+        let mut pools = BTreeMap::new();
+        for hop in path.windows(2) {
+            let (a, b) = pool_key(hop[0], hop[1]);
+            pools.entry((a, b)).or_insert_with(|| {
+                let authority = Pubkey::new_unique();
+                MockPool {
+                    mints: [mints[a], mints[b]],
+                    reserves: [
+                        token_account(mints[a], authority, RESERVE_AMOUNT),
+                        token_account(mints[b], authority, RESERVE_AMOUNT),
+                    ],
+                    rate: rates[a * NUM_MINTS + b],
+                }
+            });
+        }
+        let owner_balances =
+            |accounts: &[AccountInfo]| -> Vec<u64> { accounts.iter().map(balance).collect() };
+        let reserve_balances = |pools: &BTreeMap<(usize, usize), MockPool>| -> Vec<[u64; 2]> {
+            pools
+                .values()
+                .map(|pool| [balance(&pool.reserves[0]), balance(&pool.reserves[1])])
+                .collect()
+        };
+        let initial_balances = owner_balances(&accounts);
+
+        let mut continuation = begin(
+            &owner_info,
+            input,
+            output,
+            amount_in,
+            minimum_amount_out,
+            num_steps,
+        );
+        let end = |continuation: &Account<'static, Continuation>| -> Result<()> {
+            end(continuation, output, &owner_info)
+        };
+
+        // the route cannot end before all steps are processed
+        prop_assert_eq!(
+            end(&continuation).map_err(|err| error_code(&err)),
+            Err(Some(ErrorCode::EndIncomplete.into()))
+        );
+
+        for (i, hop) in path.windows(2).enumerate() {
+            prop_assert_eq!(continuation.steps_left as usize, num_steps - i);
+            let is_last = i == num_steps - 1;
+            let pool = &pools[&pool_key(hop[0], hop[1])];
+            let step = MockStep::new(&accounts[hop[0]], &accounts[hop[1]], pool);
+
+            let step_amount_in = continuation.amount_in.amount;
+            let balances_before = owner_balances(&accounts);
+            let reserves_before = reserve_balances(&pools);
+            if let Err(err) = process_step(action_types[i], &step, &mut continuation) {
+                // the transaction would be aborted; only rounding to zero
+                // or slippage may fail an honest route
+                let code = error_code(&err);
+                prop_assert!(
+                    code == Some(ErrorCode::ZeroSwap.into())
+                        || (is_last && code == Some(ErrorCode::MinimumOutNotMet.into())),
+                    "unexpected error {:?}",
+                    err
+                );
+                return Ok(());
+            }
+
+            // only the last step enforces the minimum amount out
+            prop_assert_eq!(
+                step.minimum_amount_out.get(),
+                Some(if is_last { minimum_amount_out } else { 0 })
+            );
+
+            // tokens only move between the owner and the pool of the step
+            let step_amount_out = continuation.amount_in.amount;
+            let balances_after = owner_balances(&accounts);
+            for (j, (before, after)) in balances_before.iter().zip(&balances_after).enumerate() {
+                let expected = if j == hop[0] {
+                    before - step_amount_in
+                } else if j == hop[1] {
+                    before + step_amount_out
+                } else {
+                    *before
+                };
+                prop_assert_eq!(*after, expected);
+            }
+            let side = usize::from(hop[0] > hop[1]);
+            for ((key, before), after) in pools
+                .keys()
+                .zip(&reserves_before)
+                .zip(reserve_balances(&pools))
+            {
+                let mut expected = *before;
+                if *key == pool_key(hop[0], hop[1]) {
+                    expected[side] += step_amount_in;
+                    expected[1 - side] -= step_amount_out;
+                }
+                prop_assert_eq!(after, expected);
+            }
+
+            prop_assert_eq!(continuation.input, accounts[hop[1]].key());
+            prop_assert_eq!(continuation.amount_in.mint, mints[hop[1]]);
+            prop_assert_eq!(continuation.steps_left as usize, num_steps - i - 1);
+        }
+
+        // no steps may be processed past the end
+        prop_assert_eq!(continuation.steps_left, 0);
+        let hop = &path[num_steps - 1..];
+        let step = MockStep::new(
+            &accounts[hop[0]],
+            &accounts[hop[1]],
+            &pools[&pool_key(hop[0], hop[1])],
+        );
+        prop_assert_eq!(
+            process_step(action_types[0], &step, &mut continuation).map_err(|err| error_code(&err)),
+            Err(Some(ErrorCode::NoMoreSteps.into()))
+        );
+        prop_assert_eq!(step.minimum_amount_out.get(), None);
+
+        // a route back to its input may not lose tokens
+        let final_balances = owner_balances(&accounts);
+        if path[0] == path[num_steps] && final_balances[path[0]] < initial_balances[path[0]] {
+            prop_assert_eq!(
+                end(&continuation).map_err(|err| error_code(&err)),
+                Err(Some(ErrorCode::BalanceLower.into()))
+            );
+            return Ok(());
+        }
+        end(&continuation).unwrap();
+
+        // only the route input and output balances changed
+        for (j, (initial, after)) in initial_balances.iter().zip(&final_balances).enumerate() {
+            if j != path[0] && j != path[num_steps] {
+                prop_assert_eq!(after, initial);
+            }
+        }
+
+        // the event matches the balance changes
+        let output_change =
+            final_balances[path[num_steps]] as i128 - initial_balances[path[num_steps]] as i128;
+        let events = swap_complete_events();
+        prop_assert_eq!(events.len(), 1);
+        let event = &events[0];
+        prop_assert_eq!(event.owner, owner);
+        prop_assert_eq!(event.amount_in, TokenAmount::new(mints[path[0]], amount_in));
+        prop_assert_eq!(event.amount_out.mint, mints[path[num_steps]]);
+        if path[0] == path[num_steps] {
+            prop_assert_eq!(
+                event.amount_out.amount as i128 - amount_in as i128,
+                output_change
+            );
+        } else {
+            prop_assert_eq!(
+                initial_balances[path[0]] - final_balances[path[0]],
+                amount_in
+            );
+            prop_assert_eq!(event.amount_out.amount as i128, output_change);
+        }
+        prop_assert!(event.amount_out.amount >= minimum_amount_out);
+
+        let quote = RETURN_DATA.with(|return_data| {
+            RouteQuote::try_from_slice(return_data.borrow().as_ref().unwrap()).unwrap()
+        });
+        prop_assert_eq!(quote.amount_in, event.amount_in);
+        prop_assert_eq!(quote.amount_out, event.amount_out);
+        Ok(())
+    }
+
+    const WITHDRAW_AMOUNT: u64 = 1_000;
+
+    fn mock_pool(mints: [&AccountInfo; 2], rate: (u64, u64)) -> MockPool {
+        let authority = Pubkey::new_unique();
+        let [a, b] = mints.map(|info| Account::<TokenAccount>::try_from(info).unwrap().mint);
+        MockPool {
+            mints: [a, b],
+            reserves: [
+                token_account(a, authority, RESERVE_AMOUNT),
+                token_account(b, authority, RESERVE_AMOUNT),
+            ],
+            rate,
+        }
+    }
+
+    /// A route which starts by withdrawing LP tokens into both tokens A and B of a pool.
+    struct SecondaryRoute {
+        owner_info: AccountInfo<'static>,
+        lp: AccountInfo<'static>,
+        a: AccountInfo<'static>,
+        b: AccountInfo<'static>,
+        c: AccountInfo<'static>,
+        /// Withdraws LP tokens into A 1:1, paying as many B from a reserve.
+        withdraw_pool: MockPool,
+        /// Swaps between A and B at 2:1.
+        swap_pool: MockPool,
+        /// Swaps between A and C at 1:1.
+        other_pool: MockPool,
+    }
+
+    impl SecondaryRoute {
+        fn new() -> Self {
+            install_syscall_stubs();
+            let owner = Pubkey::new_unique();
+            let account = |amount| token_account(Pubkey::new_unique(), owner, amount);
+            let (lp, a, b, c) = (account(WITHDRAW_AMOUNT), account(0), account(0), account(0));
+            SecondaryRoute {
+                owner_info: account_info(owner, Pubkey::default(), vec![], true),
+                withdraw_pool: mock_pool([&lp, &a], (1, 1)),
+                swap_pool: mock_pool([&a, &b], (1, 2)),
+                other_pool: mock_pool([&a, &c], (1, 1)),
+                lp,
+                a,
+                b,
+                c,
+            }
+        }
+
+        fn mint(info: &AccountInfo<'static>) -> Pubkey {
+            Account::<TokenAccount>::try_from(info).unwrap().mint
+        }
+
+        /// A withdrawal of all LP tokens into A, and into B as the secondary balance.
+        fn withdraw_step(&self) -> MockStep<'_> {
+            let reserve = token_account(Self::mint(&self.b), Pubkey::new_unique(), RESERVE_AMOUNT);
+            MockStep::new(&self.lp, &self.a, &self.withdraw_pool).with_secondary(&self.b, reserve)
+        }
+
+        /// Begins a route of `num_steps` to `output` and processes the withdrawal.
+        fn begin_with_withdraw(
+            &self,
+            output: &AccountInfo<'static>,
+            minimum_amount_out: u64,
+            num_steps: usize,
+        ) -> Account<'static, Continuation> {
+            let mut continuation = begin(
+                &self.owner_info,
+                &self.lp,
+                output,
+                WITHDRAW_AMOUNT,
+                minimum_amount_out,
+                num_steps,
+            );
+            MockSSWithdraw(&self.withdraw_step())
+                .process(&mut continuation)
+                .unwrap();
+            assert_eq!(continuation.input, self.a.key());
+            assert_eq!(
+                continuation.amount_in,
+                TokenAmount::new(Self::mint(&self.a), WITHDRAW_AMOUNT)
+            );
+            assert_eq!(continuation.secondary_input, self.b.key());
+            assert_eq!(
+                continuation.secondary_amount_in,
+                TokenAmount::new(Self::mint(&self.b), WITHDRAW_AMOUNT)
+            );
+            continuation
+        }
+
+        fn end(
+            &self,
+            continuation: &Account<'static, Continuation>,
+            output: &AccountInfo<'static>,
+        ) -> Result<()> {
+            end(continuation, output, &self.owner_info)
+        }
+    }
+
+    fn assert_secondary_consumed(continuation: &Account<Continuation>) {
+        assert_eq!(continuation.secondary_input, Pubkey::default());
+        assert_eq!(continuation.secondary_amount_in, TokenAmount::default());
+    }
+
+    #[test]
+    fn test_swap_secondary_into_primary() {
+        let route = SecondaryRoute::new();
+        let mut continuation = route.begin_with_withdraw(&route.a, 1_200, 2);
+
+        let step = MockStep::new(&route.b, &route.a, &route.swap_pool);
+        MockSSSwap(&step).process(&mut continuation).unwrap();
+        // the carried primary balance counts towards the minimum amount out
+        assert_eq!(step.minimum_amount_out.get(), Some(200));
+        assert_eq!(continuation.input, route.a.key());
+        assert_eq!(
+            continuation.amount_in,
+            TokenAmount::new(SecondaryRoute::mint(&route.a), 1_500)
+        );
+        assert_secondary_consumed(&continuation);
+        assert_eq!(balance(&route.a), 1_500);
+        assert_eq!(balance(&route.b), 0);
+
+        route.end(&continuation, &route.a).unwrap();
+    }
+
+    #[test]
+    fn test_swap_primary_into_secondary() {
+        let route = SecondaryRoute::new();
+        let mut continuation = route.begin_with_withdraw(&route.b, 1_200, 2);
+
+        let step = MockStep::new(&route.a, &route.b, &route.swap_pool);
+        MockSSSwap(&step).process(&mut continuation).unwrap();
+        // the carried secondary balance counts towards the minimum amount out
+        assert_eq!(step.minimum_amount_out.get(), Some(200));
+        assert_eq!(continuation.input, route.b.key());
+        assert_eq!(
+            continuation.amount_in,
+            TokenAmount::new(SecondaryRoute::mint(&route.b), 1_500)
+        );
+        assert_secondary_consumed(&continuation);
+        assert_eq!(balance(&route.a), 0);
+        assert_eq!(balance(&route.b), 1_500);
+
+        route.end(&continuation, &route.b).unwrap();
+    }
+
+    #[test]
+    fn test_secondary_output_mismatch() {
+        let route = SecondaryRoute::new();
+
+        // the secondary balance must be merged into the primary account
+        let mut continuation = route.begin_with_withdraw(&route.c, 0, 2);
+        let step = MockStep::new(&route.b, &route.c, &route.other_pool);
+        assert_eq!(
+            error_code(&MockSSSwap(&step).process(&mut continuation).unwrap_err()),
+            Some(ErrorCode::SecondaryOutputMismatch.into())
+        );
+        assert_eq!(step.minimum_amount_out.get(), None);
+
+        // a withdrawal may not pay both tokens into the same account
+        let route = SecondaryRoute::new();
+        let mut continuation = begin(&route.owner_info, &route.lp, &route.a, 1, 0, 1);
+        let reserve = token_account(SecondaryRoute::mint(&route.a), Pubkey::new_unique(), 1);
+        let step = MockStep::new(&route.lp, &route.a, &route.withdraw_pool)
+            .with_secondary(&route.a, reserve);
+        assert_eq!(
+            error_code(
+                &MockSSWithdraw(&step)
+                    .process(&mut continuation)
+                    .unwrap_err()
+            ),
+            Some(ErrorCode::SecondaryOutputMismatch.into())
+        );
+    }
+
+    #[test]
+    fn test_secondary_balance_in_use() {
+        let route = SecondaryRoute::new();
+        let mut continuation = route.begin_with_withdraw(&route.c, 0, 2);
+
+        let reserve = token_account(SecondaryRoute::mint(&route.lp), Pubkey::new_unique(), 1);
+        let step =
+            MockStep::new(&route.a, &route.c, &route.other_pool).with_secondary(&route.lp, reserve);
+        assert_eq!(
+            error_code(
+                &MockSSWithdraw(&step)
+                    .process(&mut continuation)
+                    .unwrap_err()
+            ),
+            Some(ErrorCode::SecondaryBalanceInUse.into())
+        );
+        assert_eq!(step.minimum_amount_out.get(), None);
+    }
+
+    #[test]
+    fn test_end_with_unconsumed_secondary() {
+        let route = SecondaryRoute::new();
+        let continuation = route.begin_with_withdraw(&route.a, 0, 1);
+        assert_eq!(
+            error_code(&route.end(&continuation, &route.a).unwrap_err()),
+            Some(ErrorCode::SecondaryBalanceUnconsumed.into())
+        );
+
+        // swapping only the primary balance leaves the secondary balance pending
+        let route = SecondaryRoute::new();
+        let mut continuation = route.begin_with_withdraw(&route.c, 0, 2);
+        let step = MockStep::new(&route.a, &route.c, &route.other_pool);
+        MockSSSwap(&step).process(&mut continuation).unwrap();
+        assert_eq!(continuation.secondary_input, route.b.key());
+        assert_eq!(
+            error_code(&route.end(&continuation, &route.c).unwrap_err()),
+            Some(ErrorCode::SecondaryBalanceUnconsumed.into())
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn test_route_invariants(params in route_params()) {
+            run_route(params)?;
+        }
+    }
+}
@@ -19,8 +19,33 @@ pub fn router_action(args: TokenStream, input: TokenStream) -> TokenStream {
                         .clone()
                         .segments
                         .into_iter()
-                        .any(|s| s.ident == "pass_through") =>
+                        .any(|s| s.ident == "pass_through" || s.ident == "pass_through_v2") =>
                 {
+                    // `pass_through` actions invoke the deprecated `#[interface]`, which
+                    // requires the target program to use `#[state]`. No action uses it anymore.
+                    let invoke = if path.segments.iter().any(|s| s.ident == "pass_through_v2") {
+                        quote! {
+                            crate::action::invoke_process_router_action(
+                                &self.swap_program,
+                                self.remaining_accounts,
+                                Self::TYPE.into(),
+                                amount_in,
+                                minimum_amount_out,
+                            )
+                        }
+                    } else {
+                        quote! {
+                            crate::router_action_processor::process_action(
+                                CpiContext::new(
+                                    self.swap_program.clone(),
+                                    self.remaining_accounts.to_vec()
+                                ),
+                                Self::TYPE.into(),
+                                amount_in,
+                                minimum_amount_out,
+                            )
+                        }
+                    };
                     quote! {
                         impl<'info> crate::processor::ActionInputOutput<'info> for ActionContext<'_, '_, '_, 'info, #action_name<'info>> {
                             fn input_account(&self) -> &Account<'info, TokenAccount> {
@@ -38,15 +63,7 @@ pub fn router_action(args: TokenStream, input: TokenStream) -> TokenStream {
                                 amount_in: u64,
                                 minimum_amount_out: u64
                             ) -> Result<()> {
-                                crate::router_action_processor::process_action(
-                                    CpiContext::new(
-                                        self.swap_program.clone(),
-                                        self.remaining_accounts.to_vec()
-                                    ),
-                                    Self::TYPE.into(),
-                                    amount_in,
-                                    minimum_amount_out,
-                                )
+                                #invoke
                             }
                         }
                     }
@@ -59,6 +76,9 @@ pub fn router_action(args: TokenStream, input: TokenStream) -> TokenStream {
                         fn output_account(&self) -> &Account<'info, TokenAccount> {
                             self.action.output_account()
                         }
+                        fn secondary_output_account(&self) -> Option<&Account<'info, TokenAccount>> {
+                            self.action.secondary_output_account()
+                        }
                     }
 
                     impl<'info> crate::processor::Processor<'info> for ActionContext<'_, '_, '_, 'info, #action_name<'info>> {
@@ -0,0 +1,28 @@
+//! Errors.
+
+/// Errors encountered while reading a [crate::Manifest].
+#[derive(Debug, thiserror::Error)]
+pub enum ManifestError {
+    #[error("failed to read CSV manifest: {0}")]
+    Csv(#[from] csv::Error),
+    #[error("failed to read JSON manifest: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("row {row}: invalid beneficiary {value}")]
+    InvalidBeneficiary { row: usize, value: String },
+    #[error("row {row}: release amount must be greater than zero")]
+    InvalidDepositAmount { row: usize },
+    #[error("row {row}: invalid release schedule from {start_ts} to {end_ts}")]
+    InvalidSchedule {
+        row: usize,
+        start_ts: i64,
+        end_ts: i64,
+    },
+    #[error("row {row}: cliff {cliff_ts} must equal start {start_ts}, as releases unlock linearly from their start")]
+    UnsupportedCliff {
+        row: usize,
+        start_ts: i64,
+        cliff_ts: i64,
+    },
+    #[error("row {row}: beneficiary {beneficiary} already has a release")]
+    DuplicateBeneficiary { row: usize, beneficiary: String },
+}
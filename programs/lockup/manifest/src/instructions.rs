@@ -0,0 +1,177 @@
+//! Instruction builders for the lockup and mint proxy programs.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{instruction::Instruction, system_program, sysvar};
+use anchor_lang::InstructionData;
+
+use crate::ManifestEntry;
+
+/// Addresses of the accounts backing a release.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReleaseAddresses {
+    /// The [lockup::Release] PDA.
+    pub release: Pubkey,
+    /// The [mint_proxy::MinterInfo] PDA authorizing the release to mint.
+    pub minter_info: Pubkey,
+}
+
+impl ReleaseAddresses {
+    /// Derives the addresses for a beneficiary of a release of `mint`.
+    pub fn derive(mint: &Pubkey, beneficiary: &Pubkey) -> Self {
+        let release = release_address(beneficiary);
+        let (minter_info, _) = Pubkey::find_program_address(
+            &[
+                b"MinterInfo",
+                mint_proxy_config_address(mint).as_ref(),
+                release.as_ref(),
+            ],
+            &mint_proxy::ID,
+        );
+        ReleaseAddresses {
+            release,
+            minter_info,
+        }
+    }
+}
+
+/// Address of the [lockup::Release] of a beneficiary.
+pub fn release_address(beneficiary: &Pubkey) -> Pubkey {
+    let (release, _) =
+        Pubkey::find_program_address(&[b"anchor", beneficiary.as_ref()], &lockup::ID);
+    release
+}
+
+/// Address of the [lockup::LockupConfig].
+pub fn lockup_config_address() -> Pubkey {
+    let (config, _) = Pubkey::find_program_address(&[b"LockupConfig"], &lockup::ID);
+    config
+}
+
+/// Address of the [mint_proxy::MintProxyConfig] of a mint.
+pub fn mint_proxy_config_address(mint: &Pubkey) -> Pubkey {
+    let (config, _) =
+        Pubkey::find_program_address(&[b"MintProxyConfig", mint.as_ref()], &mint_proxy::ID);
+    config
+}
+
+/// Accounts shared by every instruction in a plan.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PlanConfig {
+    /// Mint of the released token.
+    pub mint: Pubkey,
+    /// Owner of both the lockup and the mint proxy.
+    pub owner: Pubkey,
+    /// Pays for created accounts and receives the lamports of closed ones.
+    pub payer: Pubkey,
+}
+
+fn instruction(
+    program_id: Pubkey,
+    accounts: impl ToAccountMetas,
+    data: impl InstructionData,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Builds `mint_proxy::minter_add_v2`, authorizing the release to mint its amount.
+pub fn minter_add(config: &PlanConfig, entry: &ManifestEntry) -> Instruction {
+    let addresses = entry.addresses(&config.mint);
+    instruction(
+        mint_proxy::ID,
+        mint_proxy::accounts::MinterAddV2 {
+            config: mint_proxy_config_address(&config.mint),
+            auth: mint_proxy::accounts::Auth {
+                owner: config.owner,
+            },
+            minter: addresses.release,
+            minter_info: addresses.minter_info,
+            payer: config.payer,
+            system_program: system_program::ID,
+        },
+        mint_proxy::instruction::MinterAddV2 {
+            allowance: entry.amount,
+        },
+    )
+}
+
+/// Builds `lockup::create_release_v2`.
+pub fn create_release(config: &PlanConfig, entry: &ManifestEntry) -> Instruction {
+    let addresses = entry.addresses(&config.mint);
+    instruction(
+        lockup::ID,
+        lockup::accounts::CreateReleaseV2 {
+            config: lockup_config_address(),
+            inner: lockup::accounts::CreateRelease {
+                auth: lockup::accounts::Auth {
+                    owner: config.owner,
+                },
+                minter_info: addresses.minter_info,
+                beneficiary: entry.beneficiary,
+                release: addresses.release,
+                mint: config.mint,
+                mint_proxy_program: mint_proxy::ID,
+                payer: config.payer,
+                system_program: system_program::ID,
+                rent: sysvar::rent::ID,
+            },
+        },
+        lockup::instruction::CreateReleaseV2 {
+            release_amount: entry.amount,
+            start_ts: entry.start_ts,
+            end_ts: entry.end_ts,
+        },
+    )
+}
+
+/// Builds `lockup::revoke_release_v2`.
+pub fn revoke_release(config: &PlanConfig, beneficiary: &Pubkey) -> Instruction {
+    let mut ix = instruction(
+        lockup::ID,
+        lockup::accounts::RevokeReleaseV2 {
+            config: lockup_config_address(),
+            inner: lockup::accounts::RevokeRelease {
+                auth: lockup::accounts::Auth {
+                    owner: config.owner,
+                },
+                release: release_address(beneficiary),
+                payer: config.payer,
+            },
+        },
+        lockup::instruction::RevokeReleaseV2 {},
+    );
+    // The payer receives the lamports of the closed release.
+    for meta in ix.accounts.iter_mut() {
+        if meta.pubkey == config.payer {
+            meta.is_writable = true;
+        }
+    }
+    ix
+}
+
+/// Builds `mint_proxy::minter_remove_v2` for the [mint_proxy::MinterInfo] of an
+/// existing release, which may predate per-mint proxies.
+pub fn minter_remove(
+    config: &PlanConfig,
+    beneficiary: &Pubkey,
+    minter_info: &Pubkey,
+) -> Instruction {
+    instruction(
+        mint_proxy::ID,
+        mint_proxy::accounts::MinterRemoveV2 {
+            config: mint_proxy_config_address(&config.mint),
+            inner: mint_proxy::accounts::MinterRemove {
+                auth: mint_proxy::accounts::Auth {
+                    owner: config.owner,
+                },
+                minter: release_address(beneficiary),
+                minter_info: *minter_info,
+                payer: config.payer,
+            },
+        },
+        mint_proxy::instruction::MinterRemoveV2 {},
+    )
+}
@@ -0,0 +1,18 @@
+//! Plans batch lockup release creation from a manifest.
+//!
+//! A manifest lists one release per beneficiary. [Manifest::from_csv] and
+//! [Manifest::from_json] parse and validate it, [plan] diffs it against the
+//! [lockup::Release] accounts already on chain, and the resulting [Plan]
+//! builds the instructions required to bring the chain in line with the manifest.
+#![deny(rustdoc::all)]
+#![allow(rustdoc::missing_doc_code_examples)]
+
+mod error;
+mod instructions;
+mod manifest;
+mod plan;
+
+pub use error::*;
+pub use instructions::*;
+pub use manifest::*;
+pub use plan::*;
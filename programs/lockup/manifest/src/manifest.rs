@@ -0,0 +1,193 @@
+//! Manifest parsing and validation.
+
+use std::collections::HashSet;
+use std::io::Read;
+use std::str::FromStr;
+
+use anchor_lang::prelude::Pubkey;
+use serde::Deserialize;
+
+use crate::{release_address, ManifestError, ReleaseAddresses};
+
+/// A row of a manifest as it is written on disk.
+#[derive(Clone, Debug, Deserialize)]
+struct RawEntry {
+    beneficiary: String,
+    amount: u64,
+    start_ts: i64,
+    end_ts: i64,
+    #[serde(default)]
+    cliff_ts: Option<i64>,
+}
+
+impl RawEntry {
+    fn validate(self, row: usize) -> Result<ManifestEntry, ManifestError> {
+        let beneficiary =
+            Pubkey::from_str(&self.beneficiary).map_err(|_| ManifestError::InvalidBeneficiary {
+                row,
+                value: self.beneficiary.clone(),
+            })?;
+        if self.amount == 0 {
+            return Err(ManifestError::InvalidDepositAmount { row });
+        }
+        if !lockup::is_valid_schedule(self.start_ts, self.end_ts) {
+            return Err(ManifestError::InvalidSchedule {
+                row,
+                start_ts: self.start_ts,
+                end_ts: self.end_ts,
+            });
+        }
+        if let Some(cliff_ts) = self.cliff_ts {
+            if cliff_ts != self.start_ts {
+                return Err(ManifestError::UnsupportedCliff {
+                    row,
+                    start_ts: self.start_ts,
+                    cliff_ts,
+                });
+            }
+        }
+        Ok(ManifestEntry {
+            beneficiary,
+            amount: self.amount,
+            start_ts: self.start_ts,
+            end_ts: self.end_ts,
+        })
+    }
+}
+
+/// A validated release.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ManifestEntry {
+    /// Account able to withdraw from the release.
+    pub beneficiary: Pubkey,
+    /// Number of tokens released over the schedule.
+    pub amount: u64,
+    /// The time at which release begins.
+    pub start_ts: i64,
+    /// The time at which all tokens are released.
+    pub end_ts: i64,
+}
+
+impl ManifestEntry {
+    /// Derives the addresses of the accounts backing this entry for a release of `mint`.
+    pub fn addresses(&self, mint: &Pubkey) -> ReleaseAddresses {
+        ReleaseAddresses::derive(mint, &self.beneficiary)
+    }
+}
+
+/// A list of validated releases, at most one per beneficiary.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Manifest {
+    /// Entries, in the order they appeared in the source.
+    pub entries: Vec<ManifestEntry>,
+}
+
+impl Manifest {
+    /// Reads a CSV manifest with the header
+    /// `beneficiary,amount,start_ts,end_ts` and an optional `cliff_ts` column.
+    pub fn from_csv<R: Read>(reader: R) -> Result<Self, ManifestError> {
+        let mut reader = csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .from_reader(reader);
+        let raw = reader
+            .deserialize()
+            .collect::<Result<Vec<RawEntry>, csv::Error>>()?;
+        Self::from_raw(raw)
+    }
+
+    /// Reads a JSON manifest consisting of an array of objects with the
+    /// same fields as the CSV manifest.
+    pub fn from_json<R: Read>(reader: R) -> Result<Self, ManifestError> {
+        let raw: Vec<RawEntry> = serde_json::from_reader(reader)?;
+        Self::from_raw(raw)
+    }
+
+    /// Addresses of the [lockup::Release] accounts which should be fetched
+    /// before calling [crate::plan].
+    pub fn release_addresses(&self) -> Vec<Pubkey> {
+        self.entries
+            .iter()
+            .map(|entry| release_address(&entry.beneficiary))
+            .collect()
+    }
+
+    fn from_raw(raw: Vec<RawEntry>) -> Result<Self, ManifestError> {
+        let mut entries = Vec::with_capacity(raw.len());
+        let mut beneficiaries = HashSet::with_capacity(raw.len());
+        for (row, raw_entry) in raw.into_iter().enumerate() {
+            let entry = raw_entry.validate(row)?;
+            if !beneficiaries.insert(entry.beneficiary) {
+                return Err(ManifestError::DuplicateBeneficiary {
+                    row,
+                    beneficiary: entry.beneficiary.to_string(),
+                });
+            }
+            entries.push(entry);
+        }
+        Ok(Manifest { entries })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALICE: &str = "9qRjwMQYrkd5JvsENaYYxSCgwEuVhK4qAo5kCFHSmdmL";
+    const BOB: &str = "GyktbGXbH9kvxP8RGfWsnFtuRgC7QCQo2WBqpo3ryk7L";
+
+    #[test]
+    fn test_from_csv() {
+        let csv = format!(
+            "beneficiary,amount,start_ts,end_ts,cliff_ts\n\
+             {ALICE},1000,100,200,\n\
+             {BOB},2000,100,300,100\n"
+        );
+        let manifest = Manifest::from_csv(csv.as_bytes()).unwrap();
+        assert_eq!(manifest.entries.len(), 2);
+        assert_eq!(manifest.entries[0].beneficiary.to_string(), ALICE);
+        assert_eq!(manifest.entries[1].amount, 2000);
+        assert_eq!(manifest.entries[1].end_ts, 300);
+    }
+
+    #[test]
+    fn test_from_json() {
+        let json = format!(
+            r#"[{{ "beneficiary": "{ALICE}", "amount": 1000, "start_ts": 100, "end_ts": 200 }}]"#
+        );
+        let manifest = Manifest::from_json(json.as_bytes()).unwrap();
+        assert_eq!(manifest.entries.len(), 1);
+        assert_eq!(manifest.entries[0].start_ts, 100);
+    }
+
+    #[test]
+    fn test_invalid_schedule() {
+        let csv = format!("beneficiary,amount,start_ts,end_ts\n{ALICE},1000,200,200\n");
+        assert!(matches!(
+            Manifest::from_csv(csv.as_bytes()),
+            Err(ManifestError::InvalidSchedule { row: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn test_unsupported_cliff() {
+        let csv =
+            format!("beneficiary,amount,start_ts,end_ts,cliff_ts\n{ALICE},1000,100,200,150\n");
+        assert!(matches!(
+            Manifest::from_csv(csv.as_bytes()),
+            Err(ManifestError::UnsupportedCliff { row: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn test_duplicate_beneficiary() {
+        let csv = format!(
+            "beneficiary,amount,start_ts,end_ts\n\
+             {ALICE},1000,100,200\n\
+             {ALICE},1000,100,300\n"
+        );
+        assert!(matches!(
+            Manifest::from_csv(csv.as_bytes()),
+            Err(ManifestError::DuplicateBeneficiary { row: 1, .. })
+        ));
+    }
+}
@@ -0,0 +1,230 @@
+//! Diffing a [Manifest] against on-chain releases.
+
+use std::collections::HashMap;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use lockup::Release;
+
+use crate::{instructions, release_address, Manifest, ManifestEntry, PlanConfig};
+
+/// What must happen to bring a [ManifestEntry] on chain.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operation {
+    /// No [Release] exists, so one is created.
+    Create(ManifestEntry),
+    /// The [Release] differs from the manifest but has never been withdrawn
+    /// from, so it is revoked and created again. Holds the
+    /// [mint_proxy::MinterInfo] of the existing [Release].
+    Replace(ManifestEntry, Pubkey),
+    /// The [Release] already matches the manifest.
+    Unchanged(ManifestEntry),
+    /// The [Release] differs from the manifest but has already been withdrawn
+    /// from or is escrow-backed, so it cannot be replaced.
+    Conflict(ManifestEntry),
+}
+
+/// The operations required to apply a [Manifest].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Plan {
+    /// One operation per [ManifestEntry], in manifest order.
+    pub operations: Vec<Operation>,
+}
+
+/// Diffs `manifest` against the existing [Release] accounts, keyed by address.
+///
+/// Only the accounts at [Manifest::release_addresses] are considered;
+/// releases which are not in the manifest are left alone.
+pub fn plan(manifest: &Manifest, mint: &Pubkey, existing: &HashMap<Pubkey, Release>) -> Plan {
+    let operations = manifest
+        .entries
+        .iter()
+        .map(
+            |entry| match existing.get(&release_address(&entry.beneficiary)) {
+                None => Operation::Create(*entry),
+                Some(release) if matches(release, mint, entry) => Operation::Unchanged(*entry),
+                Some(release)
+                    if !release.is_escrow() && release.outstanding == release.start_balance =>
+                {
+                    Operation::Replace(*entry, release.minter_info)
+                }
+                Some(_) => Operation::Conflict(*entry),
+            },
+        )
+        .collect();
+    Plan { operations }
+}
+
+/// Returns true if `release` is exactly the [Release] that applying `entry`
+/// would create, including the minter it mints through.
+fn matches(release: &Release, mint: &Pubkey, entry: &ManifestEntry) -> bool {
+    release.beneficiary == entry.beneficiary
+        && release.mint == *mint
+        && release.mint_proxy_program == mint_proxy::ID
+        && release.minter_info == entry.addresses(mint).minter_info
+        && release.start_balance == entry.amount
+        && release.start_ts == entry.start_ts
+        && release.end_ts == entry.end_ts
+}
+
+impl Plan {
+    /// Entries which cannot be applied because their release was already withdrawn from.
+    pub fn conflicts(&self) -> Vec<ManifestEntry> {
+        self.operations
+            .iter()
+            .filter_map(|op| match op {
+                Operation::Conflict(entry) => Some(*entry),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Instructions closing the releases and minters that are replaced.
+    ///
+    /// These must be confirmed before [Plan::create_instructions] is sent, as
+    /// the same addresses are initialized again.
+    pub fn revoke_instructions(&self, config: &PlanConfig) -> Vec<Instruction> {
+        self.operations
+            .iter()
+            .filter_map(|op| match op {
+                Operation::Replace(entry, minter_info) => Some((entry, minter_info)),
+                _ => None,
+            })
+            .flat_map(|(entry, minter_info)| {
+                [
+                    instructions::revoke_release(config, &entry.beneficiary),
+                    instructions::minter_remove(config, &entry.beneficiary, minter_info),
+                ]
+            })
+            .collect()
+    }
+
+    /// Instructions creating every missing or replaced release, as
+    /// `minter_add` followed by `create_release` for each entry.
+    pub fn create_instructions(&self, config: &PlanConfig) -> Vec<Instruction> {
+        self.operations
+            .iter()
+            .filter_map(|op| match op {
+                Operation::Create(entry) | Operation::Replace(entry, _) => Some(entry),
+                _ => None,
+            })
+            .flat_map(|entry| {
+                [
+                    instructions::minter_add(config, entry),
+                    instructions::create_release(config, entry),
+                ]
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(amount: u64) -> ManifestEntry {
+        ManifestEntry {
+            beneficiary: Pubkey::new_unique(),
+            amount,
+            start_ts: 100,
+            end_ts: 200,
+        }
+    }
+
+    fn release(mint: &Pubkey, entry: &ManifestEntry, outstanding: u64) -> Release {
+        let mut release = Release::default();
+        release.beneficiary = entry.beneficiary;
+        release.mint_proxy_program = mint_proxy::ID;
+        release.minter_info = entry.addresses(mint).minter_info;
+        release.mint = *mint;
+        release.start_balance = entry.amount;
+        release.outstanding = outstanding;
+        release.start_ts = entry.start_ts;
+        release.end_ts = entry.end_ts;
+        release
+    }
+
+    #[test]
+    fn test_plan() {
+        let mint = Pubkey::new_unique();
+        let config = PlanConfig {
+            mint,
+            owner: Pubkey::new_unique(),
+            payer: Pubkey::new_unique(),
+        };
+        let missing = entry(1_000);
+        let unchanged = entry(2_000);
+        let changed = entry(3_000);
+        let withdrawn = entry(4_000);
+
+        let mut existing = HashMap::new();
+        existing.insert(
+            unchanged.addresses(&mint).release,
+            release(&mint, &unchanged, 2_000),
+        );
+        let stale = entry(5);
+        existing.insert(changed.addresses(&mint).release, release(&mint, &stale, 5));
+        existing.insert(
+            withdrawn.addresses(&mint).release,
+            release(&mint, &stale, 1),
+        );
+        let manifest = Manifest {
+            entries: vec![missing, unchanged, changed, withdrawn],
+        };
+
+        let plan = plan(&manifest, &mint, &existing);
+        assert_eq!(
+            plan.operations,
+            vec![
+                Operation::Create(missing),
+                Operation::Unchanged(unchanged),
+                Operation::Replace(changed, stale.addresses(&mint).minter_info),
+                Operation::Conflict(withdrawn),
+            ]
+        );
+        assert_eq!(plan.conflicts(), vec![withdrawn]);
+        assert_eq!(plan.revoke_instructions(&config).len(), 2);
+
+        let creates = plan.create_instructions(&config);
+        assert_eq!(creates.len(), 4);
+        assert_eq!(creates[0].program_id, mint_proxy::ID);
+        assert_eq!(creates[1].program_id, lockup::ID);
+        assert_eq!(
+            creates[1].accounts[4].pubkey,
+            missing.addresses(&mint).release
+        );
+    }
+
+    #[test]
+    fn test_plan_compares_minter() {
+        let mint = Pubkey::new_unique();
+        let entry = entry(1_000);
+        let address = entry.addresses(&mint).release;
+
+        let mut legacy = release(&mint, &entry, 1_000);
+        legacy.minter_info = Pubkey::new_unique();
+        let mut other_program = release(&mint, &entry, 1_000);
+        other_program.mint_proxy_program = Pubkey::new_unique();
+        let mut escrow = release(&mint, &entry, 1_000);
+        escrow.mint_proxy_program = Pubkey::default();
+        escrow.minter_info = Pubkey::default();
+
+        let manifest = Manifest {
+            entries: vec![entry],
+        };
+        let plan_with = |release: Release| {
+            let mut existing = HashMap::new();
+            existing.insert(address, release);
+            plan(&manifest, &mint, &existing).operations
+        };
+        assert_eq!(
+            plan_with(legacy.clone()),
+            vec![Operation::Replace(entry, legacy.minter_info)]
+        );
+        assert_eq!(
+            plan_with(other_program.clone()),
+            vec![Operation::Replace(entry, other_program.minter_info)]
+        );
+        assert_eq!(plan_with(escrow), vec![Operation::Conflict(entry)]);
+    }
+}
@@ -1,11 +1,93 @@
 //! Utility functions for calculating unlock schedules for a release account.
 
-use crate::Release;
+use crate::{ErrorCode, Release};
+use anchor_lang::prelude::*;
 use num_traits::ToPrimitive;
 
+/// The state of a [Release] at a point in time.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ReleaseProjection {
+    /// The timestamp projected at.
+    pub timestamp: i64,
+    /// Total amount vested at [ReleaseProjection::timestamp], including withdrawn tokens.
+    pub vested: u64,
+    /// Amount withdrawn so far. Withdrawals are not timestamped, so this is the
+    /// current withdrawn amount regardless of [ReleaseProjection::timestamp].
+    pub withdrawn: u64,
+    /// Amount available for withdrawal at [ReleaseProjection::timestamp].
+    pub available: u64,
+    /// The next timestamp after [ReleaseProjection::timestamp] at which the
+    /// vested amount increases, if any.
+    pub next_unlock_ts: Option<i64>,
+}
+
+/// Projects the state of a [Release] at the given timestamp.
+///
+/// `ts` may be before tokens were withdrawn, in which case less may have
+/// vested than was withdrawn and nothing is available.
+pub fn project(release: &Release, ts: i64) -> Result<ReleaseProjection> {
+    let vested = total_released(release, ts)?;
+    let withdrawn = withdrawn_amount(release)?;
+    Ok(ReleaseProjection {
+        timestamp: ts,
+        vested,
+        withdrawn,
+        available: vested.saturating_sub(withdrawn).min(balance(release)),
+        next_unlock_ts: next_unlock_ts(release, ts),
+    })
+}
+
+/// Projects the state of a [Release] at `num_points` evenly spaced timestamps
+/// from `start_ts` to `end_ts` inclusive, e.g. for charting.
+pub fn time_series(
+    release: &Release,
+    start_ts: i64,
+    end_ts: i64,
+    num_points: usize,
+) -> Result<Vec<ReleaseProjection>> {
+    if num_points == 0 || end_ts < start_ts {
+        return Ok(vec![]);
+    }
+    if num_points == 1 {
+        return Ok(vec![project(release, start_ts)?]);
+    }
+    let span = i128::from(end_ts) - i128::from(start_ts);
+    let intervals = (num_points - 1) as i128;
+    (0..num_points)
+        .map(|i| {
+            let offset = span * (i as i128) / intervals;
+            project(release, (i128::from(start_ts) + offset) as i64)
+        })
+        .collect()
+}
+
+/// Returns the first timestamp after `current_ts` at which the vested amount
+/// of the [Release] increases, or [None] if everything has vested.
+pub fn next_unlock_ts(release: &Release, current_ts: i64) -> Option<i64> {
+    if release.start_balance == 0 || current_ts >= release.end_ts {
+        return None;
+    }
+    // The vested amount at `t` is `floor((t - start) * balance / duration)`,
+    // so the next unit vests at `start + ceil((vested + 1) * duration / balance)`.
+    let duration =
+        u128::try_from(i128::from(release.end_ts) - i128::from(release.start_ts)).ok()?;
+    let next_vested = u128::from(total_released(release, current_ts).ok()?).checked_add(1)?;
+    let balance = u128::from(release.start_balance);
+    let offset = next_vested
+        .checked_mul(duration)?
+        .checked_add(balance - 1)?
+        .checked_div(balance)?;
+    let next_ts = i128::from(release.start_ts).checked_add(i128::try_from(offset).ok()?)?;
+    let next_ts = std::cmp::max(next_ts, i128::from(current_ts) + 1);
+    i64::try_from(std::cmp::min(next_ts, i128::from(release.end_ts))).ok()
+}
+
 /// Computes the amount of tokens available for withdrawal for a given [Release].
-pub fn available_for_withdrawal(release: &Release, current_ts: i64) -> u64 {
-    std::cmp::min(outstanding_released(release, current_ts), balance(release))
+pub fn available_for_withdrawal(release: &Release, current_ts: i64) -> Result<u64> {
+    Ok(std::cmp::min(
+        outstanding_released(release, current_ts)?,
+        balance(release),
+    ))
 }
 
 /// The amount of funds currently in the vault.
@@ -14,51 +96,54 @@ fn balance(release: &Release) -> u64 {
 }
 
 /// The amount of outstanding locked tokens released.
-fn outstanding_released(release: &Release, current_ts: i64) -> u64 {
-    total_released(release, current_ts)
-        .checked_sub(withdrawn_amount(release))
-        .unwrap()
+///
+/// Zero if less has been released at `current_ts` than has been withdrawn,
+/// which is the case for timestamps before the withdrawals.
+fn outstanding_released(release: &Release, current_ts: i64) -> Result<u64> {
+    Ok(total_released(release, current_ts)?.saturating_sub(withdrawn_amount(release)?))
 }
 
 /// Returns the amount withdrawn from this release account.
-fn withdrawn_amount(release: &Release) -> u64 {
+///
+/// Fails if the [Release] is malformed, i.e. more is outstanding than was deposited.
+fn withdrawn_amount(release: &Release) -> Result<u64> {
     release
         .start_balance
         .checked_sub(release.outstanding)
-        .unwrap()
+        .ok_or_else(|| error!(ErrorCode::U64Overflow))
 }
 
 /// Returns the total released amount up to the given ts, assuming zero
 /// withdrawals and zero funds sent to other programs.
-fn total_released(release: &Release, current_ts: i64) -> u64 {
+pub fn total_released(release: &Release, current_ts: i64) -> Result<u64> {
     if current_ts < release.start_ts {
-        0
+        Ok(0)
     } else if current_ts >= release.end_ts {
-        release.start_balance
+        Ok(release.start_balance)
     } else {
-        linear_unlock(release, current_ts).unwrap()
+        linear_unlock(release, current_ts)
     }
 }
 
 /// Computes the linear unlock.
-fn linear_unlock(release: &Release, current_ts: i64) -> Option<u64> {
-    // Signed division not supported.
-    let current_ts = current_ts as u64;
-    let start_ts = release.start_ts as u64;
-    let end_ts = release.end_ts as u64;
-
-    if current_ts <= start_ts {
-        return Some(0);
+fn linear_unlock(release: &Release, current_ts: i64) -> Result<u64> {
+    if current_ts <= release.start_ts {
+        return Ok(0);
     }
 
-    if current_ts >= end_ts {
-        return Some(release.start_balance);
+    if current_ts >= release.end_ts {
+        return Ok(release.start_balance);
     }
 
-    (current_ts.checked_sub(start_ts)? as u128)
-        .checked_mul(release.start_balance.into())?
-        .checked_div(end_ts.checked_sub(start_ts)?.into())?
-        .to_u64()
+    // Timestamps may be negative, so take differences in i128. Both are
+    // positive here and fit in a u128 along with the scaled balance.
+    let elapsed = (i128::from(current_ts) - i128::from(release.start_ts)) as u128;
+    let duration = (i128::from(release.end_ts) - i128::from(release.start_ts)) as u128;
+    elapsed
+        .checked_mul(release.start_balance.into())
+        .and_then(|released| released.checked_div(duration))
+        .and_then(|released| released.to_u64())
+        .ok_or_else(|| error!(ErrorCode::U64Overflow))
 }
 
 #[cfg(test)]
@@ -94,4 +179,110 @@ mod tests {
         let amt = linear_unlock(release, 150_000).unwrap();
         assert_eq!(amt, 500_000);
     }
+
+    #[test]
+    fn test_next_unlock_ts() {
+        let release = &mut Release::default();
+        release.start_ts = 100_000;
+        release.end_ts = 200_000;
+        release.start_balance = 1_000;
+        // one token every 100 seconds
+        assert_eq!(next_unlock_ts(release, 0), Some(100_100));
+        assert_eq!(next_unlock_ts(release, 100_000), Some(100_100));
+        assert_eq!(next_unlock_ts(release, 100_150), Some(100_200));
+        assert_eq!(next_unlock_ts(release, 199_950), Some(200_000));
+        assert_eq!(next_unlock_ts(release, 200_000), None);
+    }
+
+    #[test]
+    fn test_next_unlock_ts_many_per_second() {
+        let release = &mut Release::default();
+        release.start_ts = 100_000;
+        release.end_ts = 200_000;
+        release.start_balance = 1_000_000;
+        assert_eq!(next_unlock_ts(release, 150_000), Some(150_001));
+    }
+
+    #[test]
+    fn test_time_series() {
+        let release = &mut Release::default();
+        release.start_ts = 100_000;
+        release.end_ts = 200_000;
+        release.start_balance = 1_000_000;
+        release.outstanding = 800_000;
+        let series = time_series(release, 150_000, 250_000, 5).unwrap();
+        let vested: Vec<u64> = series.iter().map(|p| p.vested).collect();
+        assert_eq!(
+            vested,
+            vec![500_000, 750_000, 1_000_000, 1_000_000, 1_000_000]
+        );
+        let available: Vec<u64> = series.iter().map(|p| p.available).collect();
+        assert_eq!(available, vec![300_000, 550_000, 800_000, 800_000, 800_000]);
+        assert!(series.iter().all(|p| p.withdrawn == 200_000));
+        assert_eq!(series[4].next_unlock_ts, None);
+    }
+
+    #[test]
+    fn test_time_series_before_withdrawals() {
+        let release = &mut Release::default();
+        release.start_ts = 100_000;
+        release.end_ts = 200_000;
+        release.start_balance = 1_000_000;
+        release.outstanding = 600_000;
+        let series = time_series(release, 50_000, 200_000, 4).unwrap();
+        let vested: Vec<u64> = series.iter().map(|p| p.vested).collect();
+        assert_eq!(vested, vec![0, 0, 500_000, 1_000_000]);
+        let available: Vec<u64> = series.iter().map(|p| p.available).collect();
+        assert_eq!(available, vec![0, 0, 100_000, 600_000]);
+        assert!(series.iter().all(|p| p.withdrawn == 400_000));
+    }
+
+    #[test]
+    fn test_linear_unlock_negative_timestamps() {
+        let release = &mut Release::default();
+        release.start_ts = -100_000;
+        release.end_ts = 100_000;
+        release.start_balance = 1_000_000;
+        assert_eq!(total_released(release, -150_000).unwrap(), 0);
+        assert_eq!(total_released(release, -50_000).unwrap(), 250_000);
+        assert_eq!(total_released(release, 0).unwrap(), 500_000);
+        assert_eq!(total_released(release, 100_000).unwrap(), 1_000_000);
+    }
+
+    #[test]
+    fn test_linear_unlock_extremes() {
+        let release = &mut Release::default();
+        release.start_ts = i64::MIN;
+        release.end_ts = i64::MAX;
+        release.start_balance = u64::MAX;
+        assert_eq!(total_released(release, -1).unwrap(), u64::MAX / 2);
+    }
+
+    #[test]
+    fn test_malformed_release() {
+        let release = &mut Release::default();
+        release.start_ts = 100_000;
+        release.end_ts = 200_000;
+        release.start_balance = 1_000_000;
+        release.outstanding = 2_000_000;
+        assert!(matches!(
+            available_for_withdrawal(release, 150_000).unwrap_err(),
+            anchor_lang::error::Error::AnchorError(err)
+                if err.error_code_number == u32::from(crate::ErrorCode::U64Overflow)
+        ));
+        assert!(project(release, 150_000).is_err());
+    }
+
+    #[test]
+    fn test_available_before_withdrawals() {
+        let release = &mut Release::default();
+        release.start_ts = 100_000;
+        release.end_ts = 200_000;
+        release.start_balance = 1_000_000;
+        release.outstanding = 600_000;
+        assert_eq!(available_for_withdrawal(release, 50_000).unwrap(), 0);
+        assert_eq!(available_for_withdrawal(release, 120_000).unwrap(), 0);
+        assert_eq!(available_for_withdrawal(release, 150_000).unwrap(), 100_000);
+        assert_eq!(available_for_withdrawal(release, 250_000).unwrap(), 600_000);
+    }
 }
@@ -16,10 +16,14 @@ macro_rules! associated_seeds {
 }
 
 use anchor_lang::prelude::*;
-use anchor_lang::{accounts::cpi_state::CpiState, solana_program::pubkey::PUBKEY_BYTES};
+use anchor_lang::solana_program::program::set_return_data;
+use anchor_lang::{
+    accounts::{cpi_state::CpiState, state::ProgramState},
+    solana_program::pubkey::PUBKEY_BYTES,
+};
 use anchor_spl::token::{self, Mint, Token, TokenAccount};
 use mint_proxy::mint_proxy::MintProxy;
-use mint_proxy::MinterInfo;
+use mint_proxy::{MintProxyConfig, VersionedMinterInfo};
 use vipers::prelude::*;
 
 pub mod calculator;
@@ -57,56 +61,47 @@ pub mod lockup {
             start_ts: i64,
             end_ts: i64,
         ) -> Result<()> {
-            require!(release_amount != 0, InvalidDepositAmount);
-            require!(is_valid_schedule(start_ts, end_ts), InvalidSchedule);
+            let bump = *unwrap_int!(ctx.bumps.get("release"));
+            ctx.accounts
+                .create_release(bump, release_amount, start_ts, end_ts)
+        }
 
-            // minter_info validations
-            require!(
-                *ctx.accounts.minter_info.to_account_info().owner
-                    == ctx.accounts.mint_proxy_program.key(),
-                MinterInfoProgramMismatch
-            );
-            require!(
-                ctx.accounts.minter_info.allowance >= release_amount,
-                MinterAllowanceTooLow
-            );
-            require!(
-                ctx.accounts.minter_info.minter == ctx.accounts.release.key(),
-                MinterUnauthorized
-            );
+        /// Revokes a [Release].
+        #[access_control(check_auth(self, &ctx.accounts.auth))]
+        pub fn revoke_release(&self, ctx: Context<RevokeRelease>) -> Result<()> {
+            ctx.accounts.revoke_release()
+        }
 
-            let release = &mut ctx.accounts.release;
-            release.beneficiary = ctx.accounts.beneficiary.key();
-            release.mint = ctx.accounts.mint.key();
-            release.mint_proxy_program = ctx.accounts.mint_proxy_program.key();
-            release.minter_info = ctx.accounts.minter_info.key();
-            release.start_balance = release_amount;
-            release.end_ts = end_ts;
-            release.start_ts = start_ts;
-            release.created_ts = Clock::get()?.unix_timestamp;
-            release.outstanding = release_amount;
-            release.__nonce = *unwrap_int!(ctx.bumps.get("release"));
-
-            emit!(ReleaseCreatedEvent {
-                beneficiary: release.beneficiary,
-                mint: release.mint,
+        /// Creates a new [Release] backed by tokens held in escrow.
+        ///
+        /// Instead of minting through the mint proxy, `release_amount` tokens are
+        /// transferred from `source` into a vault owned by the [Release].
+        #[access_control(check_auth(self, &ctx.accounts.auth))]
+        pub fn create_escrow_release(
+            &self,
+            ctx: Context<CreateEscrowRelease>,
+            release_amount: u64,
+            start_ts: i64,
+            end_ts: i64,
+        ) -> Result<()> {
+            let release_bump = *unwrap_int!(ctx.bumps.get("release"));
+            let escrow_bump = *unwrap_int!(ctx.bumps.get("escrow"));
+            ctx.accounts.create_escrow_release(
+                release_bump,
+                escrow_bump,
                 release_amount,
-                created_at: release.created_ts,
-                start_at: release.start_ts,
-                end_at: release.end_ts,
-            });
-
-            Ok(())
+                start_ts,
+                end_ts,
+            )
         }
 
-        /// Revokes a [Release].
+        /// Revokes an escrow-backed [Release].
+        ///
+        /// Tokens which have not yet vested are returned to the treasury. Tokens
+        /// which have vested remain withdrawable by the beneficiary.
         #[access_control(check_auth(self, &ctx.accounts.auth))]
-        pub fn revoke_release(&self, ctx: Context<RevokeRelease>) -> Result<()> {
-            require!(
-                ctx.accounts.release.outstanding == ctx.accounts.release.start_balance,
-                ReleaseAlreadyRedeemedFrom
-            );
-            Ok(())
+        pub fn revoke_escrow_release(&self, ctx: Context<RevokeEscrowRelease>) -> Result<()> {
+            ctx.accounts.revoke_escrow_release()
         }
 
         /// Transfers ownership of the [Lockup] to another account.
@@ -135,7 +130,7 @@ pub mod lockup {
             // calculate amount to withdraw
             let release = &ctx.accounts.release;
             let amount =
-                calculator::available_for_withdrawal(release, Clock::get()?.unix_timestamp);
+                calculator::available_for_withdrawal(release, Clock::get()?.unix_timestamp)?;
 
             // Short circuit if withdraw amount is zero.
             if amount == 0 {
@@ -195,7 +190,7 @@ pub mod lockup {
             let amount_released = calculator::available_for_withdrawal(
                 &ctx.accounts.release,
                 Clock::get()?.unix_timestamp,
-            );
+            )?;
             // Has the given amount released?
             require!(amount <= amount_released, InsufficientWithdrawalBalance);
             // Enough mint allowance for mint?
@@ -238,16 +233,221 @@ pub mod lockup {
 
             Ok(())
         }
+
+        /// Withdraws all available tokens from an escrow-backed [Release].
+        pub fn withdraw_escrow(&self, ctx: Context<WithdrawEscrow>) -> Result<()> {
+            let amount = calculator::available_for_withdrawal(
+                &ctx.accounts.release,
+                Clock::get()?.unix_timestamp,
+            )?;
+
+            // Short circuit if withdraw amount is zero.
+            if amount == 0 {
+                return Ok(());
+            }
+
+            ctx.accounts.withdraw(amount)
+        }
+
+        /// Withdraws tokens from an escrow-backed [Release] with an amount.
+        pub fn withdraw_escrow_with_amount(
+            &self,
+            ctx: Context<WithdrawEscrow>,
+            amount: u64,
+        ) -> Result<()> {
+            // Short circuit if withdraw amount is zero.
+            if amount == 0 {
+                return Ok(());
+            }
+
+            let amount_released = calculator::available_for_withdrawal(
+                &ctx.accounts.release,
+                Clock::get()?.unix_timestamp,
+            )?;
+            // Has the given amount released?
+            require!(amount <= amount_released, InsufficientWithdrawalBalance);
+
+            ctx.accounts.withdraw(amount)
+        }
+    }
+
+    /// Copies the deprecated [Lockup] state into a new [LockupConfig].
+    ///
+    /// The state is left without an owner, so it can no longer be administered
+    /// once migrated.
+    pub fn migrate_state(ctx: Context<MigrateState>) -> Result<()> {
+        let state = &mut ctx.accounts.lockup_state;
+        require!(
+            ctx.accounts.owner.is_signer && state.owner == ctx.accounts.owner.key(),
+            Unauthorized
+        );
+
+        let config = &mut ctx.accounts.config;
+        config.bump = unwrap_bump!(ctx, "config");
+        config.owner = state.owner;
+        config.pending_owner = state.pending_owner;
+
+        state.owner = Pubkey::default();
+        state.pending_owner = Pubkey::default();
+
+        Ok(())
+    }
+
+    /// Creates a new [Release].
+    #[access_control(check_config_auth(&ctx.accounts.config, &ctx.accounts.inner.auth))]
+    pub fn create_release_v2(
+        ctx: Context<CreateReleaseV2>,
+        release_amount: u64,
+        start_ts: i64,
+        end_ts: i64,
+    ) -> Result<()> {
+        let bump = *unwrap_int!(ctx.bumps.get("release"));
+        ctx.accounts
+            .inner
+            .create_release(bump, release_amount, start_ts, end_ts)
+    }
+
+    /// Revokes a [Release].
+    #[access_control(check_config_auth(&ctx.accounts.config, &ctx.accounts.inner.auth))]
+    pub fn revoke_release_v2(ctx: Context<RevokeReleaseV2>) -> Result<()> {
+        ctx.accounts.inner.revoke_release()
+    }
+
+    /// Creates a new [Release] backed by tokens held in escrow.
+    #[access_control(check_config_auth(&ctx.accounts.config, &ctx.accounts.inner.auth))]
+    pub fn create_escrow_release_v2(
+        ctx: Context<CreateEscrowReleaseV2>,
+        release_amount: u64,
+        start_ts: i64,
+        end_ts: i64,
+    ) -> Result<()> {
+        let release_bump = *unwrap_int!(ctx.bumps.get("release"));
+        let escrow_bump = *unwrap_int!(ctx.bumps.get("escrow"));
+        ctx.accounts.inner.create_escrow_release(
+            release_bump,
+            escrow_bump,
+            release_amount,
+            start_ts,
+            end_ts,
+        )
+    }
+
+    /// Revokes an escrow-backed [Release].
+    #[access_control(check_config_auth(&ctx.accounts.config, &ctx.accounts.inner.auth))]
+    pub fn revoke_escrow_release_v2(ctx: Context<RevokeEscrowReleaseV2>) -> Result<()> {
+        ctx.accounts.inner.revoke_escrow_release()
+    }
+
+    /// Transfers ownership of the [LockupConfig] to another account.
+    #[access_control(check_config_auth(&ctx.accounts.config, &ctx.accounts.auth))]
+    pub fn transfer_ownership_v2(ctx: Context<ConfigOwnership>, next_owner: Pubkey) -> Result<()> {
+        ctx.accounts.config.pending_owner = next_owner;
+        Ok(())
+    }
+
+    /// Accepts the new ownership of the [LockupConfig].
+    pub fn accept_ownership_v2(ctx: Context<ConfigOwnership>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        require!(ctx.accounts.auth.owner.is_signer, Unauthorized);
+        require!(
+            config.pending_owner == ctx.accounts.auth.owner.key(),
+            PendingOwnerMismatch
+        );
+        config.owner = config.pending_owner;
+        config.pending_owner = Pubkey::default();
+        Ok(())
+    }
+
+    /// Withdraws all available [Release] tokens, minting through the
+    /// [mint_proxy::MintProxyConfig].
+    pub fn withdraw_v2(ctx: Context<WithdrawV2>) -> Result<()> {
+        ctx.accounts.validate()?;
+
+        let amount = calculator::available_for_withdrawal(
+            &ctx.accounts.release,
+            Clock::get()?.unix_timestamp,
+        )?;
+
+        // Short circuit if withdraw amount is zero.
+        if amount == 0 {
+            return Ok(());
+        }
+
+        ctx.accounts.withdraw(amount)
+    }
+
+    /// Withdraws tokens from the [Release] with an amount, minting through the
+    /// [mint_proxy::MintProxyConfig].
+    pub fn withdraw_with_amount_v2(ctx: Context<WithdrawV2>, amount: u64) -> Result<()> {
+        // Short circuit if withdraw amount is zero.
+        if amount == 0 {
+            return Ok(());
+        }
+
+        ctx.accounts.validate()?;
+
+        let amount_released = calculator::available_for_withdrawal(
+            &ctx.accounts.release,
+            Clock::get()?.unix_timestamp,
+        )?;
+        // Has the given amount released?
+        require!(amount <= amount_released, InsufficientWithdrawalBalance);
+
+        ctx.accounts.withdraw(amount)
+    }
+
+    /// Withdraws all available tokens from an escrow-backed [Release].
+    ///
+    /// Unlike [Lockup::withdraw_escrow], this does not require the [Lockup] state.
+    pub fn withdraw_escrow_v2(ctx: Context<WithdrawEscrow>) -> Result<()> {
+        let amount = calculator::available_for_withdrawal(
+            &ctx.accounts.release,
+            Clock::get()?.unix_timestamp,
+        )?;
+
+        // Short circuit if withdraw amount is zero.
+        if amount == 0 {
+            return Ok(());
+        }
+
+        ctx.accounts.withdraw(amount)
+    }
+
+    /// Withdraws tokens from an escrow-backed [Release] with an amount.
+    pub fn withdraw_escrow_with_amount_v2(ctx: Context<WithdrawEscrow>, amount: u64) -> Result<()> {
+        // Short circuit if withdraw amount is zero.
+        if amount == 0 {
+            return Ok(());
+        }
+
+        let amount_released = calculator::available_for_withdrawal(
+            &ctx.accounts.release,
+            Clock::get()?.unix_timestamp,
+        )?;
+        // Has the given amount released?
+        require!(amount <= amount_released, InsufficientWithdrawalBalance);
+
+        ctx.accounts.withdraw(amount)
     }
 
     /// Convenience function for UI's to calculate the withdrawable amount.
+    ///
+    /// The Borsh-encoded [AvailableForWithdrawalData] is returned via return data.
     pub fn available_for_withdrawal(ctx: Context<AvailableForWithdrawal>) -> Result<()> {
-        let available = calculator::available_for_withdrawal(
+        let amount = calculator::available_for_withdrawal(
             &ctx.accounts.release,
             ctx.accounts.clock.unix_timestamp,
-        );
-        // Log as string so that JS can read as a BN.
-        msg!(&format!("{{ \"result\": \"{}\" }}", available));
+        )?;
+        set_return_data(&AvailableForWithdrawalData { amount }.try_to_vec()?);
+        Ok(())
+    }
+
+    /// Projects the state of a [Release] at an arbitrary timestamp.
+    ///
+    /// The Borsh-encoded [calculator::ReleaseProjection] is returned via return data.
+    pub fn project_release(ctx: Context<ProjectRelease>, timestamp: i64) -> Result<()> {
+        let projection = calculator::project(&ctx.accounts.release, timestamp)?;
+        set_return_data(&projection.try_to_vec()?);
         Ok(())
     }
 }
@@ -269,7 +469,7 @@ pub struct CreateRelease<'info> {
     /// Authentication for authority of the [lockup::Lockup].
     pub auth: Auth<'info>,
     /// Minter info account.
-    pub minter_info: Account<'info, MinterInfo>,
+    pub minter_info: Account<'info, VersionedMinterInfo>,
     /// Account able to withdraw from the [Release].
     /// CHECK: Arbitrary.
     pub beneficiary: UncheckedAccount<'info>,
@@ -298,6 +498,56 @@ pub struct CreateRelease<'info> {
     pub rent: Sysvar<'info, Rent>,
 }
 
+impl<'info> CreateRelease<'info> {
+    fn create_release(
+        &mut self,
+        bump: u8,
+        release_amount: u64,
+        start_ts: i64,
+        end_ts: i64,
+    ) -> Result<()> {
+        require!(release_amount != 0, InvalidDepositAmount);
+        require!(is_valid_schedule(start_ts, end_ts), InvalidSchedule);
+
+        // minter_info validations
+        require!(
+            *self.minter_info.to_account_info().owner == self.mint_proxy_program.key(),
+            MinterInfoProgramMismatch
+        );
+        require!(
+            self.minter_info.allowance >= release_amount,
+            MinterAllowanceTooLow
+        );
+        require!(
+            self.minter_info.minter == self.release.key(),
+            MinterUnauthorized
+        );
+
+        let release = &mut self.release;
+        release.beneficiary = self.beneficiary.key();
+        release.mint = self.mint.key();
+        release.mint_proxy_program = self.mint_proxy_program.key();
+        release.minter_info = self.minter_info.key();
+        release.start_balance = release_amount;
+        release.end_ts = end_ts;
+        release.start_ts = start_ts;
+        release.created_ts = Clock::get()?.unix_timestamp;
+        release.outstanding = release_amount;
+        release.__nonce = bump;
+
+        emit!(ReleaseCreatedEvent {
+            beneficiary: release.beneficiary,
+            mint: release.mint,
+            release_amount,
+            created_at: release.created_ts,
+            start_at: release.start_ts,
+            end_at: release.end_ts,
+        });
+
+        Ok(())
+    }
+}
+
 #[derive(Accounts)]
 pub struct RevokeRelease<'info> {
     /// Authentication for authority of the [lockup::Lockup].
@@ -310,6 +560,17 @@ pub struct RevokeRelease<'info> {
     pub payer: UncheckedAccount<'info>,
 }
 
+impl<'info> RevokeRelease<'info> {
+    fn revoke_release(&self) -> Result<()> {
+        require!(!self.release.is_escrow(), ReleaseIsEscrow);
+        require!(
+            self.release.outstanding == self.release.start_balance,
+            ReleaseAlreadyRedeemedFrom
+        );
+        Ok(())
+    }
+}
+
 #[derive(Accounts)]
 pub struct Withdraw<'info> {
     /// Mint authority of the proxy.
@@ -333,7 +594,7 @@ pub struct Withdraw<'info> {
     pub unused_clock: UncheckedAccount<'info>,
     /// Minter info.
     #[account(mut)]
-    pub minter_info: Account<'info, MinterInfo>,
+    pub minter_info: Account<'info, VersionedMinterInfo>,
     /// Mint proxy program.
     pub mint_proxy_program: Program<'info, mint_proxy::program::MintProxy>,
     /// Mint proxy state.
@@ -394,12 +655,443 @@ impl<'info> Withdraw<'info> {
     }
 }
 
+#[derive(Accounts)]
+pub struct CreateEscrowRelease<'info> {
+    /// Authentication for authority of the [lockup::Lockup].
+    pub auth: Auth<'info>,
+    /// Account able to withdraw from the [Release].
+    /// CHECK: Arbitrary.
+    pub beneficiary: UncheckedAccount<'info>,
+    /// [Release] account.
+    #[account(
+        init,
+        seeds = [
+            b"anchor".as_ref(),
+            beneficiary.key().as_ref()
+        ],
+        bump,
+        space = 8 + Release::LEN,
+        payer = payer
+    )]
+    pub release: Box<Account<'info, Release>>,
+    /// [ReleaseEscrow] account.
+    #[account(
+        init,
+        seeds = [
+            b"ReleaseEscrow".as_ref(),
+            release.key().as_ref()
+        ],
+        bump,
+        space = 8 + ReleaseEscrow::LEN,
+        payer = payer
+    )]
+    pub escrow: Box<Account<'info, ReleaseEscrow>>,
+    /// Token to be released.
+    pub mint: Box<Account<'info, Mint>>,
+    /// Vault holding the escrowed tokens. Must be owned by the [Release].
+    #[account(mut)]
+    pub vault: Box<Account<'info, TokenAccount>>,
+    /// Token account which receives unvested tokens upon revocation.
+    pub treasury: Box<Account<'info, TokenAccount>>,
+    /// Token account funding the [Release].
+    #[account(mut)]
+    pub source: Box<Account<'info, TokenAccount>>,
+    /// Authority of the [CreateEscrowRelease::source].
+    pub source_authority: Signer<'info>,
+    /// Payer for the [Release] account creation.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// Token program.
+    pub token_program: Program<'info, Token>,
+    /// System program.
+    pub system_program: Program<'info, System>,
+    /// Rent sysvar.
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl<'info> CreateEscrowRelease<'info> {
+    fn validate(&self) -> Result<()> {
+        // vault validations
+        assert_keys_eq!(self.vault.owner, self.release.key(), VaultOwnerMismatch);
+        assert_keys_eq!(self.vault.mint, self.mint.key(), InvalidTokenMint);
+        require!(self.vault.amount == 0, VaultNotEmpty);
+        invariant!(self.vault.delegate.is_none());
+        invariant!(self.vault.close_authority.is_none());
+
+        // treasury validations
+        assert_keys_eq!(self.treasury.mint, self.mint.key(), InvalidTokenMint);
+
+        // source validations
+        assert_keys_eq!(self.source.mint, self.mint.key(), InvalidTokenMint);
+
+        Ok(())
+    }
+
+    fn create_escrow_release(
+        &mut self,
+        release_bump: u8,
+        escrow_bump: u8,
+        release_amount: u64,
+        start_ts: i64,
+        end_ts: i64,
+    ) -> Result<()> {
+        require!(release_amount != 0, InvalidDepositAmount);
+        require!(is_valid_schedule(start_ts, end_ts), InvalidSchedule);
+        self.validate()?;
+
+        token::transfer(
+            CpiContext::new(
+                self.token_program.to_account_info(),
+                token::Transfer {
+                    from: self.source.to_account_info(),
+                    to: self.vault.to_account_info(),
+                    authority: self.source_authority.to_account_info(),
+                },
+            ),
+            release_amount,
+        )?;
+
+        let release = &mut self.release;
+        release.beneficiary = self.beneficiary.key();
+        release.mint = self.mint.key();
+        release.mint_proxy_program = Pubkey::default();
+        release.minter_info = Pubkey::default();
+        release.start_balance = release_amount;
+        release.end_ts = end_ts;
+        release.start_ts = start_ts;
+        release.created_ts = Clock::get()?.unix_timestamp;
+        release.outstanding = release_amount;
+        release.__nonce = release_bump;
+
+        let escrow = &mut self.escrow;
+        escrow.release = release.key();
+        escrow.vault = self.vault.key();
+        escrow.treasury = self.treasury.key();
+        escrow.bump = escrow_bump;
+
+        emit!(ReleaseCreatedEvent {
+            beneficiary: release.beneficiary,
+            mint: release.mint,
+            release_amount,
+            created_at: release.created_ts,
+            start_at: release.start_ts,
+            end_at: release.end_ts,
+        });
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct RevokeEscrowRelease<'info> {
+    /// Authentication for authority of the [lockup::Lockup].
+    pub auth: Auth<'info>,
+    /// [Release] account.
+    #[account(mut)]
+    pub release: Account<'info, Release>,
+    /// [ReleaseEscrow] of the [Release].
+    #[account(has_one = release, has_one = vault, has_one = treasury)]
+    pub escrow: Account<'info, ReleaseEscrow>,
+    /// Vault holding the escrowed tokens.
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+    /// Token account which receives the unvested tokens.
+    #[account(mut)]
+    pub treasury: Account<'info, TokenAccount>,
+    /// Token program.
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> RevokeEscrowRelease<'info> {
+    fn revoke_escrow_release(&mut self) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let unvested = self.release.revoke(now)?;
+
+        if unvested > 0 {
+            let release = &self.release;
+            let beneficiary_key = release.beneficiary.to_bytes();
+            let seeds = associated_seeds!(release, &beneficiary_key);
+            token::transfer(
+                CpiContext::new_with_signer(
+                    self.token_program.to_account_info(),
+                    token::Transfer {
+                        from: self.vault.to_account_info(),
+                        to: self.treasury.to_account_info(),
+                        authority: self.release.to_account_info(),
+                    },
+                    &[&seeds[..]],
+                ),
+                unvested,
+            )?;
+        }
+
+        let release = &self.release;
+        emit!(ReleaseRevokedEvent {
+            beneficiary: release.beneficiary,
+            mint: release.mint,
+            treasury: self.treasury.key(),
+            returned_amount: unvested,
+            outstanding_amount: release.outstanding,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct WithdrawEscrow<'info> {
+    /// Owner of the [Release].
+    pub beneficiary: Signer<'info>,
+    /// [Release].
+    #[account(mut, has_one = beneficiary)]
+    pub release: Account<'info, Release>,
+    /// [ReleaseEscrow] of the [Release].
+    #[account(has_one = release, has_one = vault)]
+    pub escrow: Account<'info, ReleaseEscrow>,
+    /// Vault holding the escrowed tokens.
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+    /// Beneficiary token account.
+    #[account(mut)]
+    pub token_account: Account<'info, TokenAccount>,
+    /// Token program.
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> WithdrawEscrow<'info> {
+    /// Transfers `amount` tokens from the vault to the beneficiary.
+    fn withdraw(&mut self, amount: u64) -> Result<()> {
+        require!(
+            self.token_account.mint == self.release.mint,
+            DestinationMintMismatch,
+        );
+
+        let beneficiary_key = self.beneficiary.key().to_bytes();
+        let seeds = associated_seeds!(self.release, &beneficiary_key);
+        token::transfer(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                token::Transfer {
+                    from: self.vault.to_account_info(),
+                    to: self.token_account.to_account_info(),
+                    authority: self.release.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            amount,
+        )?;
+
+        // Bookkeeping.
+        let release = &mut self.release;
+        release.outstanding = unwrap_int!(release.outstanding.checked_sub(amount));
+
+        emit!(WithdrawEvent {
+            beneficiary: release.beneficiary,
+            mint: release.mint,
+            outstanding_amount: release.outstanding,
+            withdraw_amount: amount,
+            timestamp: Clock::get()?.unix_timestamp
+        });
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct MigrateState<'info> {
+    /// The deprecated [Lockup] state.
+    #[account(mut)]
+    pub lockup_state: ProgramState<'info, Lockup>,
+    /// Owner of the [Lockup] state.
+    pub owner: Signer<'info>,
+    /// The [LockupConfig].
+    #[account(
+        init,
+        seeds = [b"LockupConfig".as_ref()],
+        bump,
+        space = 8 + LockupConfig::LEN,
+        payer = payer
+    )]
+    pub config: Account<'info, LockupConfig>,
+    /// Payer for the [LockupConfig] account creation.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// System program.
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ConfigOwnership<'info> {
+    /// The [LockupConfig].
+    #[account(mut)]
+    pub config: Account<'info, LockupConfig>,
+    /// Current or pending owner.
+    pub auth: Auth<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreateReleaseV2<'info> {
+    /// The [LockupConfig].
+    pub config: Account<'info, LockupConfig>,
+    /// Creates the [Release].
+    pub inner: CreateRelease<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeReleaseV2<'info> {
+    /// The [LockupConfig].
+    pub config: Account<'info, LockupConfig>,
+    /// Revokes the [Release].
+    pub inner: RevokeRelease<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreateEscrowReleaseV2<'info> {
+    /// The [LockupConfig].
+    pub config: Account<'info, LockupConfig>,
+    /// Creates the [Release].
+    pub inner: CreateEscrowRelease<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeEscrowReleaseV2<'info> {
+    /// The [LockupConfig].
+    pub config: Account<'info, LockupConfig>,
+    /// Revokes the [Release].
+    pub inner: RevokeEscrowRelease<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawV2<'info> {
+    /// Mint authority of the proxy.
+    /// CHECK: Arbitrary.
+    pub proxy_mint_authority: UncheckedAccount<'info>,
+    /// Mint of the token unlocked.
+    #[account(mut)]
+    pub token_mint: Account<'info, Mint>,
+    /// Owner of the [Release].
+    pub beneficiary: Signer<'info>,
+    /// [Release].
+    #[account(mut, has_one = beneficiary)]
+    pub release: Account<'info, Release>,
+    /// Beneficiary token account.
+    #[account(mut)]
+    pub token_account: Account<'info, TokenAccount>,
+    /// Token program.
+    pub token_program: Program<'info, Token>,
+    /// Minter info.
+    #[account(mut)]
+    pub minter_info: Account<'info, VersionedMinterInfo>,
+    /// Mint proxy program.
+    pub mint_proxy_program: Program<'info, mint_proxy::program::MintProxy>,
+    /// Mint proxy config.
+    pub mint_proxy_config: Account<'info, MintProxyConfig>,
+}
+
+impl<'info> WithdrawV2<'info> {
+    fn validate(&self) -> Result<()> {
+        // proxy_mint_authority validations
+        assert_keys_eq!(
+            self.proxy_mint_authority,
+            self.mint_proxy_config.proxy_mint_authority,
+            ProxyMintAuthorityMismatch
+        );
+
+        // token_mint validations
+        require!(self.token_mint.key() == self.release.mint, InvalidTokenMint);
+        require!(
+            self.token_mint.key() == self.mint_proxy_config.token_mint,
+            MintProxyMintMismatch
+        );
+
+        // release validations
+        require!(
+            self.release.key() == self.minter_info.minter,
+            ReleaseMismatch,
+        );
+
+        // token_account validations
+        require!(
+            self.token_account.mint == self.release.mint,
+            DestinationMintMismatch,
+        );
+
+        // minter_info validations
+        require!(
+            self.minter_info.key() == self.release.minter_info,
+            MinterInfoMismatch
+        );
+
+        // mint_proxy_program validations
+        require!(
+            self.mint_proxy_program.key() == self.release.mint_proxy_program,
+            InvalidMintProxyProgram
+        );
+
+        Ok(())
+    }
+
+    /// Mints `amount` tokens to the beneficiary.
+    fn withdraw(&mut self, amount: u64) -> Result<()> {
+        require!(self.minter_info.allowance >= amount, MinterAllowanceTooLow);
+
+        let beneficiary_key = self.beneficiary.key().to_bytes();
+        let seeds = associated_seeds!(self.release, &beneficiary_key);
+        mint_proxy::cpi::perform_mint_v2(
+            CpiContext::new_with_signer(
+                self.mint_proxy_program.to_account_info(),
+                mint_proxy::cpi::accounts::PerformMintV2 {
+                    config: self.mint_proxy_config.to_account_info(),
+                    inner: mint_proxy::cpi::accounts::PerformMint {
+                        proxy_mint_authority: self.proxy_mint_authority.to_account_info(),
+                        minter: self.release.to_account_info(),
+                        token_mint: self.token_mint.to_account_info(),
+                        destination: self.token_account.to_account_info(),
+                        minter_info: self.minter_info.to_account_info(),
+                        token_program: self.token_program.to_account_info(),
+                    },
+                },
+                &[&seeds[..]],
+            ),
+            amount,
+        )?;
+
+        // Bookkeeping.
+        let release = &mut self.release;
+        release.outstanding = unwrap_int!(release.outstanding.checked_sub(amount));
+
+        emit!(WithdrawEvent {
+            beneficiary: release.beneficiary,
+            mint: release.mint,
+            outstanding_amount: release.outstanding,
+            withdraw_amount: amount,
+            timestamp: Clock::get()?.unix_timestamp
+        });
+
+        Ok(())
+    }
+}
+
 #[derive(Accounts)]
 pub struct AvailableForWithdrawal<'info> {
     pub release: Account<'info, Release>,
     pub clock: Sysvar<'info, Clock>,
 }
 
+/// Return data of [lockup::available_for_withdrawal].
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AvailableForWithdrawalData {
+    /// Amount of tokens which may currently be withdrawn.
+    pub amount: u64,
+}
+
+#[derive(Accounts)]
+pub struct ProjectRelease<'info> {
+    /// [Release] to project.
+    pub release: Account<'info, Release>,
+}
+
 /// Contains information about a beneficiary and the tokens it can claim
 /// + its release schedule.
 #[account]
@@ -437,6 +1129,72 @@ impl Release {
     pub fn nonce(&self) -> u8 {
         self.__nonce
     }
+
+    /// Returns true if this [Release] pays out of a [ReleaseEscrow] vault
+    /// rather than minting through the mint proxy.
+    pub fn is_escrow(&self) -> bool {
+        self.minter_info == Pubkey::default()
+    }
+
+    /// Ends the schedule at `now`, keeping only what has already vested.
+    ///
+    /// Returns the unvested amount, which is no longer owed to the beneficiary.
+    /// Vested tokens which have not been withdrawn remain claimable.
+    pub fn revoke(&mut self, now: i64) -> Result<u64> {
+        let vested = calculator::total_released(self, now)?;
+        let withdrawn = unwrap_int!(self.start_balance.checked_sub(self.outstanding));
+        let unvested = unwrap_int!(self.start_balance.checked_sub(vested));
+
+        self.start_balance = vested;
+        self.outstanding = unwrap_int!(vested.checked_sub(withdrawn));
+        self.end_ts = self.end_ts.min(now);
+        self.start_ts = self.start_ts.min(now);
+        Ok(unvested)
+    }
+}
+
+/// Holds the vault of a [Release] funded with existing tokens.
+#[account]
+#[derive(Default)]
+pub struct ReleaseEscrow {
+    /// The [Release] this escrow backs.
+    pub release: Pubkey,
+    /// Token account owned by the [Release] holding the escrowed tokens.
+    pub vault: Pubkey,
+    /// Token account which receives unvested tokens if the [Release] is revoked.
+    pub treasury: Pubkey,
+    /// Bump seed.
+    pub bump: u8,
+}
+
+impl ReleaseEscrow {
+    pub const LEN: usize = PUBKEY_BYTES * 3 + 1;
+}
+
+/// Configuration of the lockup. Replaces the deprecated [Lockup] state.
+///
+/// Created once by [lockup::migrate_state].
+#[account]
+#[derive(Default)]
+pub struct LockupConfig {
+    /// Bump seed.
+    pub bump: u8,
+    /// Owner that controls/creates the lockup.
+    pub owner: Pubkey,
+    /// Next owner.
+    pub pending_owner: Pubkey,
+}
+
+impl LockupConfig {
+    pub const LEN: usize = 1 + PUBKEY_BYTES * 2;
+}
+
+fn check_config_auth(config: &LockupConfig, auth: &Auth) -> Result<()> {
+    require!(
+        auth.owner.is_signer && config.owner == auth.owner.key(),
+        Unauthorized
+    );
+    Ok(())
 }
 
 fn check_auth(lockup: &Lockup, auth: &Auth) -> Result<()> {
@@ -472,6 +1230,19 @@ pub struct WithdrawEvent {
     pub timestamp: i64,
 }
 
+#[event]
+pub struct ReleaseRevokedEvent {
+    #[index]
+    pub beneficiary: Pubkey,
+    #[index]
+    pub mint: Pubkey,
+
+    pub treasury: Pubkey,
+    pub returned_amount: u64,
+    pub outstanding_amount: u64,
+    pub timestamp: i64,
+}
+
 #[error_code]
 pub enum ErrorCode {
     #[msg("The provided beneficiary was not valid.")]
@@ -516,8 +1287,89 @@ pub enum ErrorCode {
 
     #[msg("U64 overflow.")]
     U64Overflow,
+
+    #[msg("Escrow-backed releases must be revoked via revoke_escrow_release")]
+    ReleaseIsEscrow,
+    #[msg("Escrow vault must be owned by the release")]
+    VaultOwnerMismatch,
+    #[msg("Escrow vault must be empty")]
+    VaultNotEmpty,
 }
 
 pub fn is_valid_schedule(start_ts: i64, end_ts: i64) -> bool {
     end_ts > start_ts
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn escrow_release(outstanding: u64) -> Release {
+        Release {
+            start_balance: 1_000_000,
+            outstanding,
+            start_ts: 100_000,
+            end_ts: 200_000,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_revoke_keeps_vested() {
+        let release = &mut escrow_release(1_000_000);
+        assert_eq!(release.revoke(150_000).unwrap(), 500_000);
+        assert_eq!(release.start_balance, 500_000);
+        assert_eq!(release.outstanding, 500_000);
+        assert_eq!(
+            calculator::available_for_withdrawal(release, 150_000).unwrap(),
+            500_000
+        );
+        assert_eq!(
+            calculator::available_for_withdrawal(release, 300_000).unwrap(),
+            500_000
+        );
+    }
+
+    #[test]
+    fn test_revoke_after_withdrawal() {
+        // 300k of the 500k vested were withdrawn; the other 200k stay claimable.
+        let release = &mut escrow_release(700_000);
+        assert_eq!(release.revoke(150_000).unwrap(), 500_000);
+        assert_eq!(release.start_balance, 500_000);
+        assert_eq!(release.outstanding, 200_000);
+        assert_eq!(
+            calculator::available_for_withdrawal(release, 150_000).unwrap(),
+            200_000
+        );
+    }
+
+    #[test]
+    fn test_revoke_before_start() {
+        let release = &mut escrow_release(1_000_000);
+        assert_eq!(release.revoke(50_000).unwrap(), 1_000_000);
+        assert_eq!(release.start_balance, 0);
+        assert_eq!(release.outstanding, 0);
+        assert_eq!((release.start_ts, release.end_ts), (50_000, 50_000));
+        assert_eq!(
+            calculator::available_for_withdrawal(release, 300_000).unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_revoke_after_end() {
+        let release = &mut escrow_release(400_000);
+        assert_eq!(release.revoke(250_000).unwrap(), 0);
+        assert_eq!(release.start_balance, 1_000_000);
+        assert_eq!(release.outstanding, 400_000);
+        assert_eq!((release.start_ts, release.end_ts), (100_000, 200_000));
+        assert_eq!(
+            calculator::available_for_withdrawal(release, 250_000).unwrap(),
+            400_000
+        );
+
+        // revoking again later does not extend the schedule
+        assert_eq!(release.revoke(900_000).unwrap(), 0);
+        assert_eq!((release.start_ts, release.end_ts), (100_000, 200_000));
+    }
+}
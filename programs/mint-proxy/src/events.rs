@@ -0,0 +1,186 @@
+//! Events.
+
+use anchor_lang::prelude::*;
+
+/// Emitted when tokens are minted through the proxy.
+#[event]
+pub struct MintEvent {
+    /// The minter.
+    #[index]
+    pub minter: Pubkey,
+    /// Mint of the token.
+    #[index]
+    pub token_mint: Pubkey,
+    /// Token account which received the tokens.
+    pub destination: Pubkey,
+    /// Amount of tokens minted.
+    pub amount: u64,
+    /// Supply of the token after the mint.
+    pub new_supply: u64,
+}
+
+/// Emitted when a minter is added.
+#[event]
+pub struct MinterAddEvent {
+    /// The [crate::MintProxyConfig], or [crate::PROXY_STATE_ACCOUNT] if changed
+    /// through the deprecated state instructions.
+    #[index]
+    pub proxy: Pubkey,
+    /// The minter.
+    #[index]
+    pub minter: Pubkey,
+    /// Allowance of the minter.
+    pub allowance: u64,
+}
+
+/// Emitted when the allowance of a minter is updated.
+#[event]
+pub struct MinterUpdateEvent {
+    /// The [crate::MintProxyConfig], or [crate::PROXY_STATE_ACCOUNT] if changed
+    /// through the deprecated state instructions.
+    #[index]
+    pub proxy: Pubkey,
+    /// The minter.
+    #[index]
+    pub minter: Pubkey,
+    /// Allowance before the update.
+    pub previous_allowance: u64,
+    /// Allowance after the update.
+    pub allowance: u64,
+}
+
+/// Emitted when a minter is removed.
+#[event]
+pub struct MinterRemoveEvent {
+    /// The [crate::MintProxyConfig], or [crate::PROXY_STATE_ACCOUNT] if changed
+    /// through the deprecated state instructions.
+    #[index]
+    pub proxy: Pubkey,
+    /// The minter.
+    #[index]
+    pub minter: Pubkey,
+}
+
+/// Emitted when the rate limit of a minter is set.
+#[event]
+pub struct MinterSetRateLimitEvent {
+    /// The [crate::MintProxyConfig].
+    #[index]
+    pub proxy: Pubkey,
+    /// The minter.
+    #[index]
+    pub minter: Pubkey,
+    /// Length of a rate limit window. Zero if the minter is not rate limited.
+    pub window_length: i64,
+    /// Maximum amount of tokens minted per window.
+    pub max_per_window: u64,
+}
+
+/// Emitted when an ownership transfer is started.
+#[event]
+pub struct TransferOwnershipEvent {
+    /// Current owner.
+    pub owner: Pubkey,
+    /// Owner that must accept the ownership.
+    pub pending_owner: Pubkey,
+}
+
+/// Emitted when an ownership transfer is accepted.
+#[event]
+pub struct AcceptOwnershipEvent {
+    /// Previous owner.
+    pub previous_owner: Pubkey,
+    /// New owner.
+    pub owner: Pubkey,
+}
+
+/// Emitted when the guardian is set.
+#[event]
+pub struct SetGuardianEvent {
+    /// Owner which set the guardian.
+    pub owner: Pubkey,
+    /// New guardian.
+    pub guardian: Pubkey,
+}
+
+/// Emitted when minting is paused or unpaused.
+#[event]
+pub struct SetPausedEvent {
+    /// Guardian or owner which changed the pause flag.
+    pub authority: Pubkey,
+    /// The minter, if only a single minter was paused or unpaused.
+    pub minter: Option<Pubkey>,
+    /// Whether minting is now paused.
+    pub paused: bool,
+}
+
+/// Emitted when the mint authority is transferred out of the proxy.
+#[event]
+pub struct SetMintAuthorityEvent {
+    /// Mint of the token.
+    #[index]
+    pub token_mint: Pubkey,
+    /// New mint authority. [None] if the supply is now fixed.
+    pub new_authority: Option<Pubkey>,
+}
+
+/// Emitted when the approvers of allowance increases are set.
+#[event]
+pub struct SetApproversEvent {
+    /// Owner which set the approvers.
+    pub owner: Pubkey,
+    /// The approvers.
+    pub approvers: Vec<Pubkey>,
+    /// Number of approvals required.
+    pub threshold: u8,
+    /// Maximum allowance increase which does not require approval.
+    pub allowance_increase_limit: u64,
+}
+
+/// Emitted when an allowance change is proposed.
+#[event]
+pub struct AllowanceProposedEvent {
+    /// The minter.
+    #[index]
+    pub minter: Pubkey,
+    /// The proposal.
+    pub proposal: Pubkey,
+    /// Allowance at the time of the proposal.
+    pub previous_allowance: u64,
+    /// Proposed allowance.
+    pub allowance: u64,
+}
+
+/// Emitted when an allowance change is approved.
+#[event]
+pub struct AllowanceApprovedEvent {
+    /// The proposal.
+    #[index]
+    pub proposal: Pubkey,
+    /// The approver.
+    pub approver: Pubkey,
+    /// Number of approvals so far.
+    pub num_approvals: u8,
+}
+
+/// Emitted when an allowance change is executed.
+#[event]
+pub struct AllowanceExecutedEvent {
+    /// The minter.
+    #[index]
+    pub minter: Pubkey,
+    /// The proposal.
+    pub proposal: Pubkey,
+    /// Allowance before the change.
+    pub previous_allowance: u64,
+    /// Allowance after the change.
+    pub allowance: u64,
+}
+
+/// Emitted when an allowance change is canceled.
+#[event]
+pub struct AllowanceCanceledEvent {
+    /// The proposal.
+    #[index]
+    pub proposal: Pubkey,
+}
@@ -1,11 +1,17 @@
 //! Manages the minting of new Saber tokens.
 #![allow(deprecated)]
 
+use anchor_lang::accounts::state::ProgramState;
 use anchor_lang::solana_program;
+use anchor_lang::solana_program::program::set_return_data;
 use anchor_lang::{prelude::*, solana_program::pubkey::PUBKEY_BYTES};
 use anchor_spl::token::{self, Mint, SetAuthority, Token, TokenAccount};
 use vipers::prelude::*;
 
+mod events;
+
+pub use events::*;
+
 mod proxy_seeds;
 
 declare_id!("UBEBk5idELqykEEaycYtQ7iBVrCg6NmvFSzMpdr22mL");
@@ -19,6 +25,10 @@ pub const PROXY_MINT_AUTHORITY: Pubkey =
     static_pubkey::static_pubkey!("GyktbGXbH9kvxP8RGfWsnFtuRgC7QCQo2WBqpo3ryk7L");
 
 /// Stub for invoking [mint_proxy::MintProxy::perform_mint].
+///
+/// Deprecated: once the state has been migrated via [mint_proxy::migrate_state],
+/// downstream programs should use `cpi::perform_mint_v2` against the
+/// [MintProxyConfig] instead.
 #[cfg(feature = "cpi")]
 pub fn invoke_perform_mint<'a, 'b, 'c, 'info>(
     ctx: CpiContext<'a, 'b, 'c, 'info, crate::cpi::accounts::PerformMint<'info>>,
@@ -109,6 +119,10 @@ pub mod mint_proxy {
         #[access_control(only_owner(self, &ctx.accounts))]
         pub fn transfer_ownership(&mut self, ctx: Context<Auth>, next_owner: Pubkey) -> Result<()> {
             self.pending_owner = next_owner;
+            emit!(TransferOwnershipEvent {
+                owner: self.owner,
+                pending_owner: next_owner,
+            });
             Ok(())
         }
 
@@ -119,88 +133,486 @@ pub mod mint_proxy {
                 self.pending_owner == *ctx.accounts.owner.key,
                 PendingOwnerMismatch
             );
+            emit!(AcceptOwnershipEvent {
+                previous_owner: self.owner,
+                owner: self.pending_owner,
+            });
             self.owner = self.pending_owner;
             self.pending_owner = Pubkey::default();
             Ok(())
         }
 
         /// Adds a minter to the mint proxy.
-        #[access_control(only_owner(self, &ctx.accounts.auth))]
+        ///
+        /// Like the other deprecated admin instructions, this is rejected once the
+        /// state is migrated, so it cannot bypass the approvers of the [MintProxyConfig].
+        #[access_control(
+            only_unmigrated(self)
+            only_owner(self, &ctx.accounts.auth)
+        )]
         pub fn minter_add(&self, ctx: Context<MinterAdd>, allowance: u64) -> Result<()> {
-            let minter_info = &mut ctx.accounts.minter_info;
-            minter_info.minter = ctx.accounts.minter.key();
-            minter_info.allowance = allowance;
-            minter_info.__nonce = *unwrap_int!(ctx.bumps.get("minter_info"));
-            Ok(())
+            let bump = *unwrap_int!(ctx.bumps.get("minter_info"));
+            ctx.accounts
+                .add_minter(PROXY_STATE_ACCOUNT, bump, allowance)
         }
 
         /// Updates a mint's allowance.
-        #[access_control(only_owner(self, &ctx.accounts.auth))]
+        #[access_control(
+            only_unmigrated(self)
+            only_owner(self, &ctx.accounts.auth)
+            only_legacy_minter(&ctx.accounts.minter_info)
+        )]
         pub fn minter_update(&self, ctx: Context<MinterUpdate>, allowance: u64) -> Result<()> {
-            let minter_info = &mut ctx.accounts.minter_info;
-            minter_info.allowance = allowance;
-            Ok(())
+            ctx.accounts.update_minter(PROXY_STATE_ACCOUNT, allowance)
         }
 
         /// Removes a minter from the list.
-        #[access_control(only_owner(self, &ctx.accounts.auth))]
+        #[access_control(
+            only_unmigrated(self)
+            only_owner(self, &ctx.accounts.auth)
+            only_legacy_minter(&ctx.accounts.minter_info)
+        )]
         pub fn minter_remove(&self, ctx: Context<MinterRemove>) -> Result<()> {
-            Ok(())
+            ctx.accounts.remove_minter(PROXY_STATE_ACCOUNT)
         }
 
         /// Performs a mint.
+        ///
+        /// Disabled once the state is migrated, as the [MintProxyConfig] may then
+        /// pause minting, which this instruction cannot observe.
+        #[access_control(
+            only_unmigrated(self)
+            only_legacy_minter(&ctx.accounts.minter_info)
+        )]
         pub fn perform_mint(&self, ctx: Context<PerformMint>, amount: u64) -> Result<()> {
-            ctx.accounts.validate(self)?;
-
-            let minter_info = &mut ctx.accounts.minter_info;
-            require!(minter_info.allowance >= amount, MinterAllowanceExceeded);
-
-            let new_supply = unwrap_int!(ctx.accounts.token_mint.supply.checked_add(amount),);
-            require!(new_supply <= self.hard_cap, HardcapExceeded);
-
-            minter_info.allowance = unwrap_int!(minter_info.allowance.checked_sub(amount));
-            let seeds = proxy_seeds::gen_signer_seeds(&self.nonce, &self.state_associated_account);
-            let proxy_signer = &[&seeds[..]];
-            let cpi_ctx = CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                token::MintTo {
-                    mint: ctx.accounts.token_mint.to_account_info(),
-                    to: ctx.accounts.destination.to_account_info(),
-                    authority: ctx.accounts.proxy_mint_authority.to_account_info(),
-                },
-                proxy_signer,
-            );
-            token::mint_to(cpi_ctx, amount)?;
-            Ok(())
+            ctx.accounts
+                .validate(&PROXY_MINT_AUTHORITY, &self.token_mint, false)?;
+            ctx.accounts.perform_mint(
+                self.nonce,
+                &self.state_associated_account,
+                self.hard_cap,
+                amount,
+            )
         }
 
         /// Makes a different account the mint authority.
-        #[access_control(only_owner(self, &ctx.accounts.auth))]
+        #[access_control(
+            only_unmigrated(self)
+            only_owner(self, &ctx.accounts.auth)
+        )]
         pub fn set_mint_authority(
             &self,
             ctx: Context<SetMintAuthority>,
             new_authority: Pubkey,
         ) -> Result<()> {
-            let mut proxy_mint_authority = ctx.accounts.proxy_mint_authority.to_account_info();
-            proxy_mint_authority.is_signer = true;
-
-            let seeds = proxy_seeds::gen_signer_seeds(&self.nonce, &self.state_associated_account);
-            let proxy_signer = &[&seeds[..]];
-            let cpi_ctx = new_set_authority_cpi_context(
-                &proxy_mint_authority,
+            set_proxy_mint_authority(
+                &ctx.accounts.proxy_mint_authority,
                 &ctx.accounts.token_mint.to_account_info(),
                 &ctx.accounts.token_program,
+                self.nonce,
+                &self.state_associated_account,
+                Some(new_authority),
             )
-            .with_signer(proxy_signer);
+        }
+    }
 
-            token::set_authority(
-                cpi_ctx,
-                spl_token::instruction::AuthorityType::MintTokens,
-                Some(new_authority),
-            )?;
+    /// Creates a mint proxy for a new mint.
+    ///
+    /// The proxy mint authority is a PDA derived from the [MintProxyConfig].
+    pub fn new_proxy(ctx: Context<NewProxy>, nonce: u8, hard_cap: u64) -> Result<()> {
+        require!(
+            ctx.accounts.token_mint.freeze_authority.is_none(),
+            InvalidFreezeAuthority
+        );
 
-            Ok(())
-        }
+        let config_key = ctx.accounts.config.key();
+        let proxy_signer_seeds = proxy_seeds::gen_signer_seeds(&nonce, &config_key);
+        require!(
+            vipers::validate_derived_address(
+                ctx.accounts.proxy_mint_authority.key,
+                ctx.program_id,
+                &proxy_signer_seeds[..],
+            ),
+            InvalidProxyAuthority
+        );
+
+        let proxy_mint_authority = ctx.accounts.proxy_mint_authority.key();
+        let cpi_ctx = new_set_authority_cpi_context(
+            &ctx.accounts.mint_authority,
+            &ctx.accounts.token_mint.to_account_info(),
+            &ctx.accounts.token_program,
+        );
+        token::set_authority(
+            cpi_ctx,
+            spl_token::instruction::AuthorityType::MintTokens,
+            Some(proxy_mint_authority),
+        )?;
+
+        let config = &mut ctx.accounts.config;
+        config.bump = unwrap_bump!(ctx, "config");
+        config.nonce = nonce;
+        config.hard_cap = hard_cap;
+        config.proxy_mint_authority = proxy_mint_authority;
+        config.owner = ctx.accounts.owner.key();
+        config.pending_owner = Pubkey::default();
+        config.state_associated_account = config_key;
+        config.token_mint = ctx.accounts.token_mint.key();
+
+        Ok(())
+    }
+
+    /// Copies the deprecated [MintProxy] state into a new [MintProxyConfig].
+    ///
+    /// The state is left without an owner and with a hard cap of zero, so it
+    /// can neither be administered nor mint once migrated.
+    pub fn migrate_state(ctx: Context<MigrateState>) -> Result<()> {
+        let state = &mut ctx.accounts.mint_proxy_state;
+        require!(
+            ctx.accounts.owner.is_signer && state.owner == ctx.accounts.owner.key(),
+            Unauthorized
+        );
+
+        let config = &mut ctx.accounts.config;
+        config.bump = unwrap_bump!(ctx, "config");
+        config.nonce = state.nonce;
+        config.hard_cap = state.hard_cap;
+        config.proxy_mint_authority = state.proxy_mint_authority;
+        config.owner = state.owner;
+        config.pending_owner = state.pending_owner;
+        config.state_associated_account = state.state_associated_account;
+        config.token_mint = state.token_mint;
+
+        state.owner = Pubkey::default();
+        state.pending_owner = Pubkey::default();
+        state.hard_cap = 0;
+
+        Ok(())
+    }
+
+    /// Transfers ownership of the [MintProxyConfig] to another account.
+    #[access_control(only_config_owner(&ctx.accounts.config, &ctx.accounts.owner))]
+    pub fn transfer_ownership_v2(ctx: Context<ConfigAuth>, next_owner: Pubkey) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.pending_owner = next_owner;
+        emit!(TransferOwnershipEvent {
+            owner: config.owner,
+            pending_owner: next_owner,
+        });
+        Ok(())
+    }
+
+    /// Accepts the new ownership of the [MintProxyConfig].
+    pub fn accept_ownership_v2(ctx: Context<ConfigAuth>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        require!(ctx.accounts.owner.is_signer, Unauthorized);
+        require!(
+            config.pending_owner == ctx.accounts.owner.key(),
+            PendingOwnerMismatch
+        );
+        emit!(AcceptOwnershipEvent {
+            previous_owner: config.owner,
+            owner: config.pending_owner,
+        });
+        config.owner = config.pending_owner;
+        config.pending_owner = Pubkey::default();
+        Ok(())
+    }
+
+    /// Adds a minter to the [MintProxyConfig].
+    ///
+    /// The [MinterInfo] is scoped to the [MintProxyConfig].
+    #[access_control(only_config_owner(&ctx.accounts.config, &ctx.accounts.auth.owner))]
+    pub fn minter_add_v2(ctx: Context<MinterAddV2>, allowance: u64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        ctx.accounts
+            .config
+            .check_allowance_increase(0, allowance, now)?;
+        let minter_info = &mut ctx.accounts.minter_info;
+        minter_info.proxy = ctx.accounts.config.key();
+        minter_info.minter = ctx.accounts.minter.key();
+        minter_info.allowance = allowance;
+        minter_info.__nonce = unwrap_bump!(ctx, "minter_info");
+        emit!(MinterAddEvent {
+            proxy: minter_info.proxy,
+            minter: minter_info.minter,
+            allowance,
+        });
+        Ok(())
+    }
+
+    /// Updates a minter's allowance.
+    #[access_control(
+        only_config_owner(&ctx.accounts.config, &ctx.accounts.inner.auth.owner)
+        only_scoped_minter(&ctx.accounts.config, &ctx.accounts.inner.minter_info)
+    )]
+    pub fn minter_update_v2(ctx: Context<MinterUpdateV2>, allowance: u64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let previous = ctx.accounts.inner.minter_info.allowance;
+        ctx.accounts
+            .config
+            .check_allowance_increase(previous, allowance, now)?;
+        let proxy = ctx.accounts.config.key();
+        ctx.accounts.inner.update_minter(proxy, allowance)
+    }
+
+    /// Configures the approvers of allowance increases.
+    ///
+    /// Once `threshold` is non-zero, allowances may only be raised by a total of
+    /// `allowance_increase_limit` per [ALLOWANCE_INCREASE_WINDOW] across all
+    /// minters. Larger increases require an [AllowanceProposal] approved by
+    /// `threshold` approvers.
+    ///
+    /// While a threshold is set, the current approvers must approve any change by
+    /// co-signing: `threshold` of them are passed as signing remaining accounts.
+    #[access_control(only_config_owner(&ctx.accounts.config, &ctx.accounts.owner))]
+    pub fn set_approvers(
+        ctx: Context<ConfigAuth>,
+        approvers: Vec<Pubkey>,
+        threshold: u8,
+        allowance_increase_limit: u64,
+    ) -> Result<()> {
+        let approvals: Vec<Pubkey> = ctx
+            .remaining_accounts
+            .iter()
+            .filter(|account| account.is_signer)
+            .map(|account| account.key())
+            .collect();
+        let config = &mut ctx.accounts.config;
+        config.set_approvers(approvers, threshold, allowance_increase_limit, &approvals)?;
+
+        emit!(SetApproversEvent {
+            owner: config.owner,
+            approvers: config.approvers.clone(),
+            threshold,
+            allowance_increase_limit,
+        });
+        Ok(())
+    }
+
+    /// Proposes a new allowance for a minter.
+    #[access_control(
+        only_config_owner(&ctx.accounts.config, &ctx.accounts.auth.owner)
+        only_scoped_minter(&ctx.accounts.config, &ctx.accounts.minter_info)
+    )]
+    pub fn propose_allowance(ctx: Context<ProposeAllowance>, allowance: u64) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.config = ctx.accounts.config.key();
+        proposal.minter_info = ctx.accounts.minter_info.key();
+        proposal.payer = ctx.accounts.payer.key();
+        proposal.bump = unwrap_bump!(ctx, "proposal");
+        proposal.allowance = allowance;
+        proposal.created_at = Clock::get()?.unix_timestamp;
+        proposal.approvals = vec![];
+
+        emit!(AllowanceProposedEvent {
+            minter: ctx.accounts.minter_info.minter,
+            proposal: proposal.key(),
+            previous_allowance: ctx.accounts.minter_info.allowance,
+            allowance,
+        });
+        Ok(())
+    }
+
+    /// Approves an [AllowanceProposal]. Callable by the approvers of the [MintProxyConfig].
+    pub fn approve_allowance(ctx: Context<ApproveAllowance>) -> Result<()> {
+        let approver = ctx.accounts.approver.key();
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.approve(&ctx.accounts.config, approver)?;
+
+        emit!(AllowanceApprovedEvent {
+            proposal: proposal.key(),
+            approver,
+            num_approvals: proposal.approvals.len() as u8,
+        });
+        Ok(())
+    }
+
+    /// Applies an [AllowanceProposal] which has reached the approval threshold.
+    /// Anyone may execute it.
+    pub fn execute_allowance(ctx: Context<ExecuteAllowance>) -> Result<()> {
+        let proposal = &ctx.accounts.proposal;
+        ctx.accounts.config.check_approvals(&proposal.approvals)?;
+
+        let minter_info = &mut ctx.accounts.minter_info;
+        let previous_allowance = minter_info.allowance;
+        minter_info.allowance = proposal.allowance;
+
+        emit!(AllowanceExecutedEvent {
+            minter: minter_info.minter,
+            proposal: proposal.key(),
+            previous_allowance,
+            allowance: proposal.allowance,
+        });
+        Ok(())
+    }
+
+    /// Cancels an [AllowanceProposal].
+    #[access_control(only_config_owner(&ctx.accounts.config, &ctx.accounts.owner))]
+    pub fn cancel_allowance(ctx: Context<CancelAllowance>) -> Result<()> {
+        emit!(AllowanceCanceledEvent {
+            proposal: ctx.accounts.proposal.key(),
+        });
+        Ok(())
+    }
+
+    /// Removes a minter.
+    #[access_control(
+        only_config_owner(&ctx.accounts.config, &ctx.accounts.inner.auth.owner)
+        only_scoped_minter(&ctx.accounts.config, &ctx.accounts.inner.minter_info)
+    )]
+    pub fn minter_remove_v2(ctx: Context<MinterRemoveV2>) -> Result<()> {
+        let proxy = ctx.accounts.config.key();
+        ctx.accounts.inner.remove_minter(proxy)
+    }
+
+    /// Performs a mint against the [MintProxyConfig].
+    #[access_control(only_scoped_minter(&ctx.accounts.config, &ctx.accounts.inner.minter_info))]
+    pub fn perform_mint_v2(ctx: Context<PerformMintV2>, amount: u64) -> Result<()> {
+        let config = &ctx.accounts.config;
+        ctx.accounts.inner.validate(
+            &config.proxy_mint_authority,
+            &config.token_mint,
+            config.paused,
+        )?;
+        let supply_cap = unwrap_int!(config.supply_cap(Clock::get()?.unix_timestamp));
+        ctx.accounts.inner.perform_mint(
+            config.nonce,
+            &config.state_associated_account,
+            supply_cap,
+            amount,
+        )
+    }
+
+    /// Transfers the mint authority of the token mint out of the proxy.
+    ///
+    /// If `new_authority` is [None], the supply of the token is permanently fixed.
+    /// The owner should be a timelock so that token holders can observe this in advance.
+    #[access_control(only_config_owner(&ctx.accounts.config, &ctx.accounts.owner))]
+    pub fn set_mint_authority_v2(
+        ctx: Context<SetMintAuthorityV2>,
+        new_authority: Option<Pubkey>,
+    ) -> Result<()> {
+        let config = &ctx.accounts.config;
+        set_proxy_mint_authority(
+            &ctx.accounts.proxy_mint_authority,
+            &ctx.accounts.token_mint.to_account_info(),
+            &ctx.accounts.token_program,
+            config.nonce,
+            &config.state_associated_account,
+            new_authority,
+        )
+    }
+
+    /// Sets the guardian, which may pause minting.
+    #[access_control(only_config_owner(&ctx.accounts.config, &ctx.accounts.owner))]
+    pub fn set_guardian(ctx: Context<ConfigAuth>, guardian: Pubkey) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.guardian = guardian;
+        emit!(SetGuardianEvent {
+            owner: config.owner,
+            guardian,
+        });
+        Ok(())
+    }
+
+    /// Pauses all minting. Callable by the guardian or the owner.
+    #[access_control(only_guardian_or_owner(&ctx.accounts.config, &ctx.accounts.authority))]
+    pub fn pause(ctx: Context<SetPaused>) -> Result<()> {
+        ctx.accounts.set_paused(true)
+    }
+
+    /// Unpauses minting. Callable by the owner only.
+    #[access_control(only_config_owner(&ctx.accounts.config, &ctx.accounts.authority))]
+    pub fn unpause(ctx: Context<SetPaused>) -> Result<()> {
+        ctx.accounts.set_paused(false)
+    }
+
+    /// Pauses a single minter. Callable by the guardian or the owner.
+    ///
+    /// A [MinterInfo] in the legacy layout is migrated first, funded by the payer.
+    #[access_control(
+        only_guardian_or_owner(&ctx.accounts.config, &ctx.accounts.authority)
+        only_scoped_minter(&ctx.accounts.config, &ctx.accounts.minter_info)
+    )]
+    pub fn pause_minter(ctx: Context<SetMinterPaused>) -> Result<()> {
+        ctx.accounts.set_paused(true)
+    }
+
+    /// Unpauses a single minter. Callable by the owner only.
+    #[access_control(
+        only_config_owner(&ctx.accounts.config, &ctx.accounts.authority)
+        only_scoped_minter(&ctx.accounts.config, &ctx.accounts.minter_info)
+    )]
+    pub fn unpause_minter(ctx: Context<SetMinterPaused>) -> Result<()> {
+        ctx.accounts.set_paused(false)
+    }
+
+    /// Appends a point to the emission schedule of the [MintProxyConfig].
+    ///
+    /// Points must be in the future and may never lower the supply cap. Once the
+    /// schedule is non-empty, it replaces the [MintProxyConfig::hard_cap].
+    #[access_control(only_config_owner(&ctx.accounts.config, &ctx.accounts.owner))]
+    pub fn append_emission_point(
+        ctx: Context<ConfigAuth>,
+        timestamp: i64,
+        max_supply: u64,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        ctx.accounts.config.append_emission_point(
+            EmissionPoint {
+                timestamp,
+                max_supply,
+            },
+            now,
+        )
+    }
+
+    /// Sets the rate limit of a minter. The current window starts now.
+    ///
+    /// A `window_length` of zero disables rate limiting.
+    #[access_control(
+        only_config_owner(&ctx.accounts.config, &ctx.accounts.inner.auth.owner)
+        only_scoped_minter(&ctx.accounts.config, &ctx.accounts.inner.minter_info)
+    )]
+    pub fn minter_set_rate_limit(
+        ctx: Context<MinterUpdateV2>,
+        window_length: i64,
+        max_per_window: u64,
+    ) -> Result<()> {
+        require!(window_length >= 0, InvalidRateLimit);
+        let minter_info = &mut ctx.accounts.inner.minter_info;
+        minter_info.window_length = window_length;
+        minter_info.max_per_window = max_per_window;
+        minter_info.window_start = Clock::get()?.unix_timestamp;
+        minter_info.minted_in_window = 0;
+
+        emit!(MinterSetRateLimitEvent {
+            proxy: ctx.accounts.config.key(),
+            minter: minter_info.minter,
+            window_length,
+            max_per_window,
+        });
+        Ok(())
+    }
+
+    /// Grows a [MinterInfo] created with an older layout to [MinterInfo::LEN].
+    ///
+    /// New fields are zero-initialized: the minter is not rate limited, and its
+    /// accounting starts from the migration. Until then, the minter is read in
+    /// the legacy layout of [VersionedMinterInfo].
+    /// Anyone may migrate a [MinterInfo]; the payer funds the additional rent.
+    pub fn migrate_minter_info(ctx: Context<MigrateMinterInfo>) -> Result<()> {
+        ctx.accounts.migrate()
+    }
+
+    /// Computes how many tokens a minter may currently mint.
+    ///
+    /// The Borsh-encoded [MinterCapacity] is returned via return data.
+    pub fn minter_capacity(ctx: Context<ViewMinter>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let capacity = unwrap_int!(ctx.accounts.minter_info.capacity(now));
+        set_return_data(&capacity.try_to_vec()?);
+        Ok(())
     }
 }
 
@@ -243,6 +655,370 @@ pub struct SetMintAuthority<'info> {
     pub token_program: Program<'info, Token>,
 }
 
+/// Accounts for [mint_proxy::new_proxy].
+#[derive(Accounts)]
+pub struct NewProxy<'info> {
+    /// Current mint authority.
+    pub mint_authority: Signer<'info>,
+
+    /// New mint authority. PDA.
+    /// CHECK: Validated in the instruction.
+    pub proxy_mint_authority: UncheckedAccount<'info>,
+
+    /// Owner of the mint proxy.
+    /// CHECK: Arbitrary
+    pub owner: UncheckedAccount<'info>,
+
+    /// Token mint to mint.
+    #[account(mut)]
+    pub token_mint: Account<'info, Mint>,
+
+    /// The [MintProxyConfig].
+    #[account(
+        init,
+        seeds = [
+            b"MintProxyConfig".as_ref(),
+            token_mint.key().as_ref()
+        ],
+        bump,
+        space = 8 + MintProxyConfig::LEN,
+        payer = payer
+    )]
+    pub config: Account<'info, MintProxyConfig>,
+
+    /// Payer for creating the [MintProxyConfig].
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Token program.
+    pub token_program: Program<'info, Token>,
+
+    /// System program.
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts for [mint_proxy::migrate_state].
+#[derive(Accounts)]
+pub struct MigrateState<'info> {
+    /// The deprecated [MintProxy] state.
+    #[account(mut)]
+    pub mint_proxy_state: ProgramState<'info, MintProxy>,
+
+    /// Owner of the [MintProxy] state.
+    pub owner: Signer<'info>,
+
+    /// The [MintProxyConfig].
+    #[account(
+        init,
+        seeds = [
+            b"MintProxyConfig".as_ref(),
+            mint_proxy_state.token_mint.as_ref()
+        ],
+        bump,
+        space = 8 + MintProxyConfig::LEN,
+        payer = payer
+    )]
+    pub config: Account<'info, MintProxyConfig>,
+
+    /// Payer for creating the [MintProxyConfig].
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// System program.
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts for owner-only updates of a [MintProxyConfig].
+#[derive(Accounts)]
+pub struct ConfigAuth<'info> {
+    /// The [MintProxyConfig].
+    #[account(mut)]
+    pub config: Account<'info, MintProxyConfig>,
+    /// Current or pending owner.
+    pub owner: Signer<'info>,
+}
+
+/// Accounts for [mint_proxy::propose_allowance].
+#[derive(Accounts)]
+pub struct ProposeAllowance<'info> {
+    /// The [MintProxyConfig].
+    pub config: Account<'info, MintProxyConfig>,
+    /// Owner of the mint proxy.
+    pub auth: Auth<'info>,
+    /// Minter information.
+    pub minter_info: Account<'info, VersionedMinterInfo>,
+    /// The [AllowanceProposal].
+    #[account(
+        init,
+        seeds = [
+            b"AllowanceProposal".as_ref(),
+            minter_info.key().as_ref()
+        ],
+        bump,
+        space = 8 + AllowanceProposal::LEN,
+        payer = payer
+    )]
+    pub proposal: Account<'info, AllowanceProposal>,
+    /// Payer for creating the [AllowanceProposal]. Refunded once it is closed.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// System program.
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts for [mint_proxy::approve_allowance].
+#[derive(Accounts)]
+pub struct ApproveAllowance<'info> {
+    /// The [MintProxyConfig].
+    pub config: Account<'info, MintProxyConfig>,
+    /// The [AllowanceProposal].
+    #[account(mut, has_one = config)]
+    pub proposal: Account<'info, AllowanceProposal>,
+    /// An approver of the [MintProxyConfig].
+    pub approver: Signer<'info>,
+}
+
+/// Accounts for [mint_proxy::execute_allowance].
+#[derive(Accounts)]
+pub struct ExecuteAllowance<'info> {
+    /// The [MintProxyConfig].
+    pub config: Account<'info, MintProxyConfig>,
+    /// The [AllowanceProposal].
+    #[account(mut, has_one = config, has_one = minter_info, has_one = payer, close = payer)]
+    pub proposal: Account<'info, AllowanceProposal>,
+    /// Minter information.
+    #[account(mut)]
+    pub minter_info: Account<'info, VersionedMinterInfo>,
+    /// Payer of the [AllowanceProposal], which receives its lamports.
+    /// CHECK: Checked by the [AllowanceProposal].
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+}
+
+/// Accounts for [mint_proxy::cancel_allowance].
+#[derive(Accounts)]
+pub struct CancelAllowance<'info> {
+    /// The [MintProxyConfig].
+    pub config: Account<'info, MintProxyConfig>,
+    /// Owner of the mint proxy.
+    pub owner: Signer<'info>,
+    /// The [AllowanceProposal].
+    #[account(mut, has_one = config, has_one = payer, close = payer)]
+    pub proposal: Account<'info, AllowanceProposal>,
+    /// Payer of the [AllowanceProposal], which receives its lamports.
+    /// CHECK: Checked by the [AllowanceProposal].
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+}
+
+/// Accounts for [mint_proxy::pause] and [mint_proxy::unpause].
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    /// The [MintProxyConfig].
+    #[account(mut)]
+    pub config: Account<'info, MintProxyConfig>,
+    /// The guardian or owner.
+    pub authority: Signer<'info>,
+}
+
+impl<'info> SetPaused<'info> {
+    fn set_paused(&mut self, paused: bool) -> Result<()> {
+        self.config.paused = paused;
+        emit!(SetPausedEvent {
+            authority: self.authority.key(),
+            minter: None,
+            paused,
+        });
+        Ok(())
+    }
+}
+
+/// Accounts for [mint_proxy::pause_minter] and [mint_proxy::unpause_minter].
+#[derive(Accounts)]
+pub struct SetMinterPaused<'info> {
+    /// The [MintProxyConfig].
+    pub config: Account<'info, MintProxyConfig>,
+    /// The guardian or owner.
+    pub authority: Signer<'info>,
+    /// Minter information.
+    #[account(mut)]
+    pub minter_info: Account<'info, VersionedMinterInfo>,
+    /// Payer of the rent of a [MinterInfo] grown to pause it.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// System program.
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SetMinterPaused<'info> {
+    fn set_paused(&mut self, paused: bool) -> Result<()> {
+        // The legacy layout cannot hold the pause, so pausing must not depend
+        // on someone migrating the minter first.
+        if paused && self.minter_info.is_legacy() {
+            grow_minter_info(
+                &self.minter_info.to_account_info(),
+                &self.payer,
+                &self.system_program,
+            )?;
+            self.minter_info.legacy = false;
+        }
+        self.minter_info.paused = paused;
+        emit!(SetPausedEvent {
+            authority: self.authority.key(),
+            minter: Some(self.minter_info.minter),
+            paused,
+        });
+        Ok(())
+    }
+}
+
+/// Accounts for [mint_proxy::set_mint_authority_v2].
+#[derive(Accounts)]
+pub struct SetMintAuthorityV2<'info> {
+    /// The [MintProxyConfig].
+    #[account(has_one = proxy_mint_authority, has_one = token_mint)]
+    pub config: Account<'info, MintProxyConfig>,
+    /// Owner of the [MintProxyConfig].
+    pub owner: Signer<'info>,
+    /// CHECK: Checked by the [MintProxyConfig].
+    pub proxy_mint_authority: UncheckedAccount<'info>,
+    /// Token mint.
+    #[account(mut)]
+    pub token_mint: Account<'info, Mint>,
+    /// The [Token] program.
+    pub token_program: Program<'info, Token>,
+}
+
+/// Accounts for [mint_proxy::minter_add_v2].
+#[derive(Accounts)]
+pub struct MinterAddV2<'info> {
+    /// The [MintProxyConfig].
+    #[account(mut)]
+    pub config: Account<'info, MintProxyConfig>,
+
+    /// Owner of the mint proxy.
+    pub auth: Auth<'info>,
+
+    /// Account to authorize as a minter.
+    /// CHECK: Arbitrary.
+    pub minter: UncheckedAccount<'info>,
+
+    /// Information about the minter.
+    #[account(
+        init,
+        seeds = [
+            b"MinterInfo".as_ref(),
+            config.key().as_ref(),
+            minter.key().as_ref()
+        ],
+        bump,
+        space = 8 + MinterInfo::LEN,
+        payer = payer
+    )]
+    pub minter_info: Account<'info, VersionedMinterInfo>,
+
+    /// Payer for creating the minter.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// System program.
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts for [mint_proxy::minter_update_v2].
+#[derive(Accounts)]
+pub struct MinterUpdateV2<'info> {
+    /// The [MintProxyConfig].
+    #[account(mut)]
+    pub config: Account<'info, MintProxyConfig>,
+    /// Updates the minter.
+    pub inner: MinterUpdate<'info>,
+}
+
+/// Accounts for [mint_proxy::minter_remove_v2].
+#[derive(Accounts)]
+pub struct MinterRemoveV2<'info> {
+    /// The [MintProxyConfig].
+    pub config: Account<'info, MintProxyConfig>,
+    /// Removes the minter.
+    pub inner: MinterRemove<'info>,
+}
+
+/// Accounts for [mint_proxy::perform_mint_v2].
+#[derive(Accounts)]
+pub struct PerformMintV2<'info> {
+    /// The [MintProxyConfig].
+    pub config: Account<'info, MintProxyConfig>,
+    /// Performs the mint.
+    pub inner: PerformMint<'info>,
+}
+
+/// Accounts for [mint_proxy::migrate_minter_info].
+#[derive(Accounts)]
+pub struct MigrateMinterInfo<'info> {
+    /// The [MinterInfo] to migrate.
+    /// CHECK: May not deserialize before migration. The discriminator is checked.
+    #[account(mut, owner = crate::ID)]
+    pub minter_info: UncheckedAccount<'info>,
+    /// Payer of the additional rent.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// System program.
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateMinterInfo<'info> {
+    fn migrate(&self) -> Result<()> {
+        let minter_info = self.minter_info.to_account_info();
+        let new_len = 8 + MinterInfo::LEN;
+        {
+            let data = minter_info.try_borrow_data()?;
+            invariant!(
+                data.len() >= 8
+                    && data[..8] == <MinterInfo as anchor_lang::Discriminator>::discriminator(),
+                "minter_info discriminator"
+            );
+            if data.len() >= new_len {
+                return Ok(());
+            }
+        }
+
+        grow_minter_info(&minter_info, &self.payer, &self.system_program)
+    }
+}
+
+/// Grows a [MinterInfo] account to [MinterInfo::LEN], zero-initializing the
+/// new fields. The `payer` funds the additional rent.
+fn grow_minter_info<'info>(
+    minter_info: &AccountInfo<'info>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    let new_len = 8 + MinterInfo::LEN;
+    let rent = Rent::get()?
+        .minimum_balance(new_len)
+        .saturating_sub(minter_info.lamports());
+    if rent > 0 {
+        solana_program::program::invoke(
+            &solana_program::system_instruction::transfer(payer.key, minter_info.key, rent),
+            &[
+                payer.to_account_info(),
+                minter_info.clone(),
+                system_program.to_account_info(),
+            ],
+        )?;
+    }
+    minter_info.realloc(new_len, true)?;
+    Ok(())
+}
+
+/// Accounts for [mint_proxy::minter_capacity].
+#[derive(Accounts)]
+pub struct ViewMinter<'info> {
+    /// Minter information.
+    pub minter_info: Account<'info, VersionedMinterInfo>,
+}
+
 /// Adds a minter.
 #[derive(Accounts)]
 pub struct MinterAdd<'info> {
@@ -264,7 +1040,7 @@ pub struct MinterAdd<'info> {
         space = 8 + MinterInfo::LEN,
         payer = payer
     )]
-    pub minter_info: Account<'info, MinterInfo>,
+    pub minter_info: Account<'info, VersionedMinterInfo>,
 
     /// Payer for creating the minter.
     #[account(mut)]
@@ -289,7 +1065,7 @@ pub struct MinterRemove<'info> {
 
     /// Information about the minter.
     #[account(mut, has_one = minter, close = payer)]
-    pub minter_info: Account<'info, MinterInfo>,
+    pub minter_info: Account<'info, VersionedMinterInfo>,
 
     /// Account which receives the freed lamports
     /// CHECK: Arbitrary.
@@ -304,7 +1080,7 @@ pub struct MinterUpdate<'info> {
     pub auth: Auth<'info>,
     /// Information about the minter.
     #[account(mut)]
-    pub minter_info: Account<'info, MinterInfo>,
+    pub minter_info: Account<'info, VersionedMinterInfo>,
 }
 
 /// Accounts for the perform_mint instruction.
@@ -327,22 +1103,112 @@ pub struct PerformMint<'info> {
 
     /// Minter information.
     #[account(mut, has_one = minter)]
-    pub minter_info: Account<'info, MinterInfo>,
+    pub minter_info: Account<'info, VersionedMinterInfo>,
 
     /// SPL Token program.
     pub token_program: Program<'info, Token>,
 }
 
 impl<'info> PerformMint<'info> {
-    fn validate(&self, state: &MintProxy) -> Result<()> {
-        assert_keys_eq!(self.proxy_mint_authority, PROXY_MINT_AUTHORITY);
+    fn validate(
+        &self,
+        proxy_mint_authority: &Pubkey,
+        token_mint: &Pubkey,
+        paused: bool,
+    ) -> Result<()> {
+        require!(!paused, MintingPaused);
+        require!(!self.minter_info.paused, MinterPaused);
+        assert_keys_eq!(self.proxy_mint_authority, *proxy_mint_authority);
         require!(self.minter.is_signer, Unauthorized);
         assert_keys_eq!(self.minter_info.minter, self.minter, Unauthorized);
 
-        assert_keys_eq!(state.token_mint, self.token_mint);
+        assert_keys_eq!(*token_mint, self.token_mint);
 
         Ok(())
     }
+
+    /// Mints `amount` tokens to the destination, deducting the minter's allowance.
+    fn perform_mint(
+        &mut self,
+        nonce: u8,
+        state_associated_account: &Pubkey,
+        hard_cap: u64,
+        amount: u64,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let minter_info = &mut self.minter_info;
+        require!(minter_info.allowance >= amount, MinterAllowanceExceeded);
+        minter_info.record_rate_limited_mint(amount, now)?;
+
+        let new_supply = unwrap_int!(self.token_mint.supply.checked_add(amount),);
+        require!(new_supply <= hard_cap, HardcapExceeded);
+
+        minter_info.allowance = unwrap_int!(minter_info.allowance.checked_sub(amount));
+        minter_info.total_minted = unwrap_int!(minter_info.total_minted.checked_add(amount));
+        minter_info.mint_count = unwrap_int!(minter_info.mint_count.checked_add(1));
+        minter_info.last_mint_ts = now;
+        let seeds = proxy_seeds::gen_signer_seeds(&nonce, state_associated_account);
+        let proxy_signer = &[&seeds[..]];
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            token::MintTo {
+                mint: self.token_mint.to_account_info(),
+                to: self.destination.to_account_info(),
+                authority: self.proxy_mint_authority.to_account_info(),
+            },
+            proxy_signer,
+        );
+        token::mint_to(cpi_ctx, amount)?;
+
+        emit!(MintEvent {
+            minter: self.minter.key(),
+            token_mint: self.token_mint.key(),
+            destination: self.destination.key(),
+            amount,
+            new_supply,
+        });
+        Ok(())
+    }
+}
+
+impl<'info> MinterAdd<'info> {
+    fn add_minter(&mut self, proxy: Pubkey, bump: u8, allowance: u64) -> Result<()> {
+        let minter_info = &mut self.minter_info;
+        minter_info.minter = self.minter.key();
+        minter_info.allowance = allowance;
+        minter_info.__nonce = bump;
+        emit!(MinterAddEvent {
+            proxy,
+            minter: minter_info.minter,
+            allowance,
+        });
+        Ok(())
+    }
+}
+
+impl<'info> MinterUpdate<'info> {
+    fn update_minter(&mut self, proxy: Pubkey, allowance: u64) -> Result<()> {
+        let minter_info = &mut self.minter_info;
+        let previous_allowance = minter_info.allowance;
+        minter_info.allowance = allowance;
+        emit!(MinterUpdateEvent {
+            proxy,
+            minter: minter_info.minter,
+            previous_allowance,
+            allowance,
+        });
+        Ok(())
+    }
+}
+
+impl<'info> MinterRemove<'info> {
+    fn remove_minter(&self, proxy: Pubkey) -> Result<()> {
+        emit!(MinterRemoveEvent {
+            proxy,
+            minter: self.minter.key(),
+        });
+        Ok(())
+    }
 }
 
 /// One who can mint.
@@ -357,10 +1223,199 @@ pub struct MinterInfo {
     /// Nonce field to the struct to hold the bump seed for the program derived address,
     /// sourced from `<https://github.com/project-serum/anchor/blob/ec6888a3b9f702bc41bd3266e7dd70116df3549c/lang/attribute/account/src/lib.rs#L220-L221.>`.
     __nonce: u8,
+
+    /// Length of a rate limit window in seconds. Zero if the minter is not rate limited.
+    pub window_length: i64,
+    /// Maximum number of tokens that can be minted per window.
+    pub max_per_window: u64,
+    /// Start of the current window.
+    pub window_start: i64,
+    /// Number of tokens minted in the current window.
+    pub minted_in_window: u64,
+
+    /// Total number of tokens minted by this minter.
+    pub total_minted: u64,
+    /// When this minter last minted.
+    pub last_mint_ts: i64,
+    /// Number of mints performed by this minter.
+    pub mint_count: u64,
+
+    /// If true, this minter may not mint.
+    pub paused: bool,
+
+    /// The [MintProxyConfig] this minter belongs to. [Pubkey::default] for
+    /// minters created before proxies were per-mint, which belong to the SBR proxy.
+    pub proxy: Pubkey,
 }
 
 impl MinterInfo {
-    pub const LEN: usize = PUBKEY_BYTES + 8 + 1;
+    pub const LEN: usize = PUBKEY_BYTES + 8 + 1 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 1 + PUBKEY_BYTES;
+
+    /// Returns true if the minter is subject to a rate limit.
+    pub fn is_rate_limited(&self) -> bool {
+        self.window_length > 0
+    }
+
+    /// Returns the start of the window containing `now`.
+    fn current_window_start(&self, now: i64) -> i64 {
+        if now >= self.window_start.saturating_add(self.window_length) {
+            now
+        } else {
+            self.window_start
+        }
+    }
+
+    /// Computes the number of tokens that can be minted at `now`.
+    pub fn capacity(&self, now: i64) -> Option<MinterCapacity> {
+        if !self.is_rate_limited() {
+            return Some(MinterCapacity {
+                remaining: self.allowance,
+                window_end: None,
+            });
+        }
+        let window_start = self.current_window_start(now);
+        let minted_in_window = if window_start == self.window_start {
+            self.minted_in_window
+        } else {
+            0
+        };
+        let window_remaining = self.max_per_window.saturating_sub(minted_in_window);
+        Some(MinterCapacity {
+            remaining: self.allowance.min(window_remaining),
+            window_end: Some(window_start.checked_add(self.window_length)?),
+        })
+    }
+
+    /// Records a mint of `amount` tokens at `now` against the rate limit, if any.
+    fn record_rate_limited_mint(&mut self, amount: u64, now: i64) -> Result<()> {
+        if !self.is_rate_limited() {
+            return Ok(());
+        }
+        let window_start = self.current_window_start(now);
+        if window_start != self.window_start {
+            self.window_start = window_start;
+            self.minted_in_window = 0;
+        }
+        let minted_in_window = unwrap_int!(self.minted_in_window.checked_add(amount));
+        require!(minted_in_window <= self.max_per_window, RateLimitExceeded);
+        self.minted_in_window = minted_in_window;
+        Ok(())
+    }
+}
+
+/// A [MinterInfo] stored in either the current or the legacy layout.
+///
+/// Accounts created before [MinterInfo::LEN] grew only hold the minter, allowance
+/// and nonce. They read the newer fields as their defaults, and persist only the
+/// legacy fields until [mint_proxy::migrate_minter_info] grows them, so existing
+/// minters keep working after the upgrade.
+#[derive(Clone, Default)]
+pub struct VersionedMinterInfo {
+    info: MinterInfo,
+    legacy: bool,
+}
+
+impl VersionedMinterInfo {
+    /// Returns true if the account still has the legacy layout.
+    pub fn is_legacy(&self) -> bool {
+        self.legacy
+    }
+}
+
+impl MinterInfo {
+    /// Length of the layout written before rate limits, statistics, pausing and proxies.
+    pub const LEGACY_LEN: usize = PUBKEY_BYTES + 8 + 1;
+}
+
+impl std::ops::Deref for VersionedMinterInfo {
+    type Target = MinterInfo;
+
+    fn deref(&self) -> &MinterInfo {
+        &self.info
+    }
+}
+
+impl std::ops::DerefMut for VersionedMinterInfo {
+    fn deref_mut(&mut self) -> &mut MinterInfo {
+        &mut self.info
+    }
+}
+
+impl AccountSerialize for VersionedMinterInfo {
+    fn try_serialize<W: std::io::Write>(&self, writer: &mut W) -> Result<()> {
+        if !self.legacy {
+            return self.info.try_serialize(writer);
+        }
+        // The legacy layout cannot hold a rate limit, a pause or a proxy.
+        // Statistics are only kept once the account is migrated.
+        require!(
+            !self.info.is_rate_limited()
+                && !self.info.paused
+                && self.info.proxy == Pubkey::default(),
+            MinterInfoNotMigrated
+        );
+        let info = &self.info;
+        (
+            <MinterInfo as anchor_lang::Discriminator>::discriminator(),
+            info.minter,
+            info.allowance,
+            info.__nonce,
+        )
+            .serialize(writer)
+            .map_err(|_| error!(anchor_lang::error::ErrorCode::AccountDidNotSerialize))
+    }
+}
+
+impl AccountDeserialize for VersionedMinterInfo {
+    fn try_deserialize(buf: &mut &[u8]) -> Result<Self> {
+        if buf.len() < 8 {
+            return Err(anchor_lang::error::ErrorCode::AccountDiscriminatorNotFound.into());
+        }
+        if buf[..8] != <MinterInfo as anchor_lang::Discriminator>::discriminator() {
+            return Err(anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch.into());
+        }
+        Self::try_deserialize_unchecked(buf)
+    }
+
+    fn try_deserialize_unchecked(buf: &mut &[u8]) -> Result<Self> {
+        let data = &buf[8..];
+        if data.len() >= MinterInfo::LEN {
+            let info = MinterInfo::try_deserialize_unchecked(buf)?;
+            return Ok(Self {
+                info,
+                legacy: false,
+            });
+        }
+        if data.len() < MinterInfo::LEGACY_LEN {
+            return Err(anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into());
+        }
+        let mut padded = data.to_vec();
+        padded.resize(MinterInfo::LEN, 0);
+        let info = MinterInfo::deserialize(&mut padded.as_slice())
+            .map_err(|_| error!(anchor_lang::error::ErrorCode::AccountDidNotDeserialize))?;
+        Ok(Self { info, legacy: true })
+    }
+}
+
+impl Owner for VersionedMinterInfo {
+    fn owner() -> Pubkey {
+        crate::ID
+    }
+}
+
+impl anchor_lang::Discriminator for VersionedMinterInfo {
+    fn discriminator() -> [u8; 8] {
+        <MinterInfo as anchor_lang::Discriminator>::discriminator()
+    }
+}
+
+/// Return data of [mint_proxy::minter_capacity].
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MinterCapacity {
+    /// Number of tokens the minter may currently mint.
+    pub remaining: u64,
+    /// When the current rate limit window ends, if the minter is rate limited.
+    pub window_end: Option<i64>,
 }
 
 /// Information about the mint proxy.
@@ -384,6 +1439,252 @@ pub struct MintProxyInfo {
     pub token_mint: Pubkey,
 }
 
+/// Configuration of a mint proxy for a single mint. Replaces the deprecated [MintProxy] state.
+///
+/// The SBR proxy is created by [mint_proxy::migrate_state]; proxies for other
+/// mints are created by [mint_proxy::new_proxy].
+#[account]
+#[derive(Default)]
+pub struct MintProxyConfig {
+    /// Bump seed of the [MintProxyConfig].
+    pub bump: u8,
+    /// Nonce for allowing the proxy mint authority to sign.
+    pub nonce: u8,
+    /// Maximum number of tokens that can be issued.
+    pub hard_cap: u64,
+    /// Account which is the authority over minted tokens.
+    pub proxy_mint_authority: Pubkey,
+    /// Owner account which can perform admin operations.
+    pub owner: Pubkey,
+    /// Next owner account.
+    pub pending_owner: Pubkey,
+    /// Account the proxy mint authority is derived from.
+    pub state_associated_account: Pubkey,
+    /// Mint of the token to be minted
+    pub token_mint: Pubkey,
+    /// Account which may pause minting. Only the owner may unpause.
+    pub guardian: Pubkey,
+    /// If true, no minter may mint.
+    pub paused: bool,
+    /// Maximum total allowance increase per [ALLOWANCE_INCREASE_WINDOW] which
+    /// does not require an [AllowanceProposal].
+    pub allowance_increase_limit: u64,
+    /// Number of approvers required to execute an [AllowanceProposal].
+    /// Zero if allowance changes do not require approval.
+    pub approval_threshold: u8,
+    /// Points of the piecewise-linear supply cap, sorted by timestamp.
+    /// If empty, the supply cap is the [MintProxyConfig::hard_cap]. Otherwise the
+    /// schedule starts at the hard cap when the first point was appended.
+    pub emission_schedule: Vec<EmissionPoint>,
+    /// Accounts which may approve an [AllowanceProposal].
+    pub approvers: Vec<Pubkey>,
+    /// Start of the current allowance increase window.
+    pub allowance_increase_window_start: i64,
+    /// Total allowance increase without approval in the current window.
+    pub allowance_increased_in_window: u64,
+}
+
+impl MintProxyConfig {
+    pub const LEN: usize = 1
+        + 1
+        + 8
+        + PUBKEY_BYTES * 6
+        + 1
+        + 8
+        + 1
+        + 4
+        + EmissionPoint::LEN * MAX_EMISSION_POINTS
+        + 4
+        + PUBKEY_BYTES * MAX_APPROVERS
+        + 8
+        + 8;
+
+    /// Records an allowance change from `previous` to `allowance` at `now`
+    /// against the [MintProxyConfig::allowance_increase_limit].
+    ///
+    /// Increases are summed over all minters within a window, so that neither
+    /// repeated updates nor removing and re-adding a minter exceed the limit.
+    fn check_allowance_increase(&mut self, previous: u64, allowance: u64, now: i64) -> Result<()> {
+        let increase = allowance.saturating_sub(previous);
+        if self.approval_threshold == 0 || increase == 0 {
+            return Ok(());
+        }
+        if now
+            >= self
+                .allowance_increase_window_start
+                .saturating_add(ALLOWANCE_INCREASE_WINDOW)
+        {
+            self.allowance_increase_window_start = now;
+            self.allowance_increased_in_window = 0;
+        }
+        let increased = unwrap_int!(self.allowance_increased_in_window.checked_add(increase));
+        require!(
+            increased <= self.allowance_increase_limit,
+            AllowanceIncreaseRequiresApproval
+        );
+        self.allowance_increased_in_window = increased;
+        Ok(())
+    }
+
+    /// Ensures at least [MintProxyConfig::approval_threshold] of the current
+    /// approvers are among `approvals`.
+    fn check_approvals(&self, approvals: &[Pubkey]) -> Result<()> {
+        let num_approvals = self
+            .approvers
+            .iter()
+            .filter(|approver| approvals.contains(approver))
+            .count();
+        require!(
+            num_approvals >= usize::from(self.approval_threshold),
+            ApprovalThresholdNotMet
+        );
+        Ok(())
+    }
+
+    /// Replaces the approvers of allowance increases.
+    ///
+    /// While a threshold is set, the change must be approved by the current approvers.
+    fn set_approvers(
+        &mut self,
+        approvers: Vec<Pubkey>,
+        threshold: u8,
+        allowance_increase_limit: u64,
+        approvals: &[Pubkey],
+    ) -> Result<()> {
+        require!(approvers.len() <= MAX_APPROVERS, InvalidApprovers);
+        require!(usize::from(threshold) <= approvers.len(), InvalidApprovers);
+        for (i, approver) in approvers.iter().enumerate() {
+            require!(!approvers[..i].contains(approver), InvalidApprovers);
+        }
+        self.check_approvals(approvals)?;
+
+        self.approvers = approvers;
+        self.approval_threshold = threshold;
+        self.allowance_increase_limit = allowance_increase_limit;
+        Ok(())
+    }
+
+    /// Computes the maximum supply at `now`.
+    ///
+    /// The schedule is interpolated linearly between points and is flat before the
+    /// first and after the last point. The [MintProxyConfig::hard_cap] only applies
+    /// while the schedule is empty.
+    pub fn supply_cap(&self, now: i64) -> Option<u64> {
+        let scheduled = match self
+            .emission_schedule
+            .iter()
+            .position(|point| point.timestamp > now)
+        {
+            None => match self.emission_schedule.last() {
+                Some(last) => last.max_supply,
+                None => return Some(self.hard_cap),
+            },
+            Some(0) => self.emission_schedule[0].max_supply,
+            Some(i) => {
+                let prev = self.emission_schedule[i - 1];
+                let next = self.emission_schedule[i];
+                let elapsed = (now as i128).checked_sub(prev.timestamp as i128)?;
+                let duration = (next.timestamp as i128).checked_sub(prev.timestamp as i128)?;
+                let delta = (next.max_supply as i128).checked_sub(prev.max_supply as i128)?;
+                let interpolated = (prev.max_supply as i128)
+                    .checked_add(delta.checked_mul(elapsed)?.checked_div(duration)?)?;
+                u64::try_from(interpolated).ok()?
+            }
+        };
+        Some(scheduled)
+    }
+
+    /// Appends a point to the emission schedule.
+    ///
+    /// The first point is preceded by a point at `now` at the current
+    /// [MintProxyConfig::hard_cap], so that the cap grows linearly from it
+    /// rather than jumping when the schedule starts.
+    fn append_emission_point(&mut self, point: EmissionPoint, now: i64) -> Result<()> {
+        require!(point.timestamp > now, InvalidEmissionPoint);
+        let start = EmissionPoint {
+            timestamp: now,
+            max_supply: self.hard_cap,
+        };
+        let (last, added) = match self.emission_schedule.last() {
+            Some(last) => (*last, 1),
+            None => (start, 2),
+        };
+        require!(
+            self.emission_schedule.len() + added <= MAX_EMISSION_POINTS,
+            EmissionScheduleFull
+        );
+        require!(
+            point.timestamp > last.timestamp && point.max_supply >= last.max_supply,
+            InvalidEmissionPoint
+        );
+        if self.emission_schedule.is_empty() {
+            self.emission_schedule.push(start);
+        }
+        self.emission_schedule.push(point);
+        Ok(())
+    }
+}
+
+/// Maximum number of approvers of a [MintProxyConfig].
+pub const MAX_APPROVERS: usize = 10;
+
+/// Length in seconds of the window over which allowance increases without
+/// approval are limited.
+pub const ALLOWANCE_INCREASE_WINDOW: i64 = 24 * 60 * 60;
+
+/// A proposed change of the allowance of a minter, awaiting approval.
+#[account]
+#[derive(Default)]
+pub struct AllowanceProposal {
+    /// The [MintProxyConfig].
+    pub config: Pubkey,
+    /// The [MinterInfo] whose allowance changes.
+    pub minter_info: Pubkey,
+    /// Account which paid for this proposal and is refunded once it is closed.
+    pub payer: Pubkey,
+    /// Bump seed.
+    pub bump: u8,
+    /// Proposed allowance.
+    pub allowance: u64,
+    /// When the proposal was created.
+    pub created_at: i64,
+    /// Approvers which have approved the proposal.
+    pub approvals: Vec<Pubkey>,
+}
+
+impl AllowanceProposal {
+    pub const LEN: usize = PUBKEY_BYTES * 3 + 1 + 8 + 8 + 4 + PUBKEY_BYTES * MAX_APPROVERS;
+
+    /// Records the approval of `approver`, which must be a current approver.
+    ///
+    /// Approvals of accounts which are no longer approvers are dropped, so the
+    /// approvals never exceed [MAX_APPROVERS].
+    fn approve(&mut self, config: &MintProxyConfig, approver: Pubkey) -> Result<()> {
+        require!(config.approvers.contains(&approver), Unauthorized);
+        self.approvals
+            .retain(|approval| config.approvers.contains(approval));
+        require!(!self.approvals.contains(&approver), AlreadyApproved);
+        self.approvals.push(approver);
+        Ok(())
+    }
+}
+
+/// Maximum number of points in the emission schedule of a [MintProxyConfig].
+pub const MAX_EMISSION_POINTS: usize = 32;
+
+/// A point of an emission schedule.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EmissionPoint {
+    /// Time at which the supply cap is reached.
+    pub timestamp: i64,
+    /// Maximum supply at the timestamp.
+    pub max_supply: u64,
+}
+
+impl EmissionPoint {
+    pub const LEN: usize = 8 + 8;
+}
+
 /// Ensures the function is only called by the owner of the mint proxy.
 fn only_owner(state: &MintProxy, auth: &Auth) -> Result<()> {
     require!(
@@ -393,6 +1694,77 @@ fn only_owner(state: &MintProxy, auth: &Auth) -> Result<()> {
     Ok(())
 }
 
+/// Ensures the deprecated [MintProxy] state has not been migrated into a
+/// [MintProxyConfig] by [mint_proxy::migrate_state], which clears its owner.
+fn only_unmigrated(state: &MintProxy) -> Result<()> {
+    require!(state.owner != Pubkey::default(), StateMigrated);
+    Ok(())
+}
+
+/// Ensures the [MinterInfo] belongs to the deprecated [MintProxy] state
+/// rather than to a [MintProxyConfig].
+fn only_legacy_minter(minter_info: &MinterInfo) -> Result<()> {
+    require!(minter_info.proxy == Pubkey::default(), MinterProxyMismatch);
+    Ok(())
+}
+
+/// Ensures the function is only called by the owner of the [MintProxyConfig].
+fn only_config_owner(config: &MintProxyConfig, owner: &Signer) -> Result<()> {
+    require!(owner.is_signer && config.owner == owner.key(), Unauthorized);
+    Ok(())
+}
+
+/// Ensures the [MinterInfo] belongs to the [MintProxyConfig].
+fn only_scoped_minter(config: &Account<MintProxyConfig>, minter_info: &MinterInfo) -> Result<()> {
+    let in_scope = if minter_info.proxy == Pubkey::default() {
+        config.state_associated_account == PROXY_STATE_ACCOUNT
+    } else {
+        minter_info.proxy == config.key()
+    };
+    require!(in_scope, MinterProxyMismatch);
+    Ok(())
+}
+
+/// Ensures the function is only called by the guardian or the owner of the [MintProxyConfig].
+fn only_guardian_or_owner(config: &MintProxyConfig, authority: &Signer) -> Result<()> {
+    require!(
+        authority.is_signer
+            && (config.guardian == authority.key() || config.owner == authority.key()),
+        Unauthorized
+    );
+    Ok(())
+}
+
+/// Transfers the mint authority away from the proxy mint authority.
+fn set_proxy_mint_authority<'info>(
+    proxy_mint_authority: &AccountInfo<'info>,
+    token_mint: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    nonce: u8,
+    state_associated_account: &Pubkey,
+    new_authority: Option<Pubkey>,
+) -> Result<()> {
+    let mut proxy_mint_authority = proxy_mint_authority.clone();
+    proxy_mint_authority.is_signer = true;
+
+    let seeds = proxy_seeds::gen_signer_seeds(&nonce, state_associated_account);
+    let proxy_signer = &[&seeds[..]];
+    let cpi_ctx = new_set_authority_cpi_context(&proxy_mint_authority, token_mint, token_program)
+        .with_signer(proxy_signer);
+
+    token::set_authority(
+        cpi_ctx,
+        spl_token::instruction::AuthorityType::MintTokens,
+        new_authority,
+    )?;
+
+    emit!(SetMintAuthorityEvent {
+        token_mint: token_mint.key(),
+        new_authority,
+    });
+    Ok(())
+}
+
 /// Sets the mint authority.
 fn new_set_authority_cpi_context<'a, 'b, 'c, 'info>(
     current_authority: &AccountInfo<'info>,
@@ -436,4 +1808,443 @@ pub enum ErrorCode {
     MinterAllowanceExceeded,
     #[msg("U64 overflow.")]
     U64Overflow,
+    #[msg("Minter rate limit exceeded.")]
+    RateLimitExceeded,
+    #[msg("Rate limit window length must not be negative.")]
+    InvalidRateLimit,
+    #[msg("Emission schedule is full.")]
+    EmissionScheduleFull,
+    #[msg("Emission points must be in the future and must not lower the supply cap.")]
+    InvalidEmissionPoint,
+    #[msg("Minting is paused.")]
+    MintingPaused,
+    #[msg("Minter is paused.")]
+    MinterPaused,
+    #[msg("Minter does not belong to this mint proxy.")]
+    MinterProxyMismatch,
+    #[msg("Invalid approvers or threshold.")]
+    InvalidApprovers,
+    #[msg("Allowance increase requires an approved proposal.")]
+    AllowanceIncreaseRequiresApproval,
+    #[msg("Approver has already approved.")]
+    AlreadyApproved,
+    #[msg("Approval threshold not met.")]
+    ApprovalThresholdNotMet,
+    #[msg("Mint proxy state has been migrated; use the _v2 instructions.")]
+    StateMigrated,
+    #[msg("Minter info must be migrated first.")]
+    MinterInfoNotMigrated,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rate_limited(allowance: u64) -> MinterInfo {
+        MinterInfo {
+            allowance,
+            window_length: 100,
+            max_per_window: 1_000,
+            window_start: 0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_unlimited_capacity() {
+        let info = MinterInfo {
+            allowance: 5_000,
+            ..Default::default()
+        };
+        assert_eq!(
+            info.capacity(1234),
+            Some(MinterCapacity {
+                remaining: 5_000,
+                window_end: None
+            })
+        );
+    }
+
+    #[test]
+    fn test_rate_limit_window() {
+        let mut info = rate_limited(5_000);
+        info.record_rate_limited_mint(600, 10).unwrap();
+        assert_eq!(info.capacity(50).unwrap().remaining, 400);
+        assert!(info.record_rate_limited_mint(401, 50).is_err());
+        info.record_rate_limited_mint(400, 99).unwrap();
+        assert_eq!(info.capacity(99).unwrap().remaining, 0);
+
+        // window refills
+        assert_eq!(
+            info.capacity(100),
+            Some(MinterCapacity {
+                remaining: 1_000,
+                window_end: Some(200)
+            })
+        );
+        info.record_rate_limited_mint(1_000, 150).unwrap();
+        assert_eq!(info.window_start, 150);
+        assert_eq!(info.capacity(249).unwrap().remaining, 0);
+    }
+
+    fn config_with_schedule(points: &[(i64, u64)]) -> MintProxyConfig {
+        MintProxyConfig {
+            hard_cap: 10_000,
+            emission_schedule: points
+                .iter()
+                .map(|&(timestamp, max_supply)| EmissionPoint {
+                    timestamp,
+                    max_supply,
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_supply_cap_without_schedule() {
+        let config = config_with_schedule(&[]);
+        assert_eq!(config.supply_cap(100), Some(10_000));
+    }
+
+    #[test]
+    fn test_supply_cap_interpolates() {
+        let config = config_with_schedule(&[(100, 1_000), (200, 3_000), (300, 20_000)]);
+        assert_eq!(config.supply_cap(0), Some(1_000));
+        assert_eq!(config.supply_cap(100), Some(1_000));
+        assert_eq!(config.supply_cap(150), Some(2_000));
+        assert_eq!(config.supply_cap(200), Some(3_000));
+        assert_eq!(config.supply_cap(210), Some(4_700));
+        // the schedule replaces the hard cap
+        assert_eq!(config.supply_cap(290), Some(18_300));
+        assert_eq!(config.supply_cap(1_000), Some(20_000));
+    }
+
+    #[test]
+    fn test_first_emission_point() {
+        let mut config = config_with_schedule(&[]);
+        let point = |timestamp, max_supply| EmissionPoint {
+            timestamp,
+            max_supply,
+        };
+        // lowers the hard cap
+        assert!(config
+            .append_emission_point(point(200, 9_999), 100)
+            .is_err());
+        assert!(config.emission_schedule.is_empty());
+
+        config
+            .append_emission_point(point(200, 20_000), 100)
+            .unwrap();
+        assert_eq!(
+            config.emission_schedule,
+            vec![point(100, 10_000), point(200, 20_000)]
+        );
+        // grows from the hard cap rather than jumping to the first point
+        assert_eq!(config.supply_cap(50), Some(10_000));
+        assert_eq!(config.supply_cap(100), Some(10_000));
+        assert_eq!(config.supply_cap(150), Some(15_000));
+        assert_eq!(config.supply_cap(300), Some(20_000));
+    }
+
+    #[test]
+    fn test_append_emission_point() {
+        let mut config = config_with_schedule(&[(100, 1_000)]);
+        let point = |timestamp, max_supply| EmissionPoint {
+            timestamp,
+            max_supply,
+        };
+        // in the past
+        assert!(config
+            .append_emission_point(point(150, 2_000), 200)
+            .is_err());
+        // lowers the cap
+        assert!(config.append_emission_point(point(300, 999), 200).is_err());
+        config
+            .append_emission_point(point(300, 2_000), 200)
+            .unwrap();
+        // not after the last point
+        assert!(config
+            .append_emission_point(point(300, 3_000), 200)
+            .is_err());
+        assert_eq!(config.emission_schedule.len(), 2);
+    }
+
+    #[test]
+    fn test_only_unmigrated() {
+        let mut state = MintProxy {
+            nonce: 0,
+            hard_cap: 10_000,
+            proxy_mint_authority: PROXY_MINT_AUTHORITY,
+            owner: Pubkey::new_unique(),
+            pending_owner: Pubkey::default(),
+            state_associated_account: PROXY_STATE_ACCOUNT,
+            token_mint: Pubkey::new_unique(),
+        };
+        assert!(only_unmigrated(&state).is_ok());
+
+        // as left by migrate_state
+        state.owner = Pubkey::default();
+        state.hard_cap = 0;
+        assert!(matches!(
+            only_unmigrated(&state).unwrap_err(),
+            anchor_lang::error::Error::AnchorError(err)
+                if err.error_code_number == u32::from(ErrorCode::StateMigrated)
+        ));
+    }
+
+    #[test]
+    fn test_only_legacy_minter() {
+        let legacy = MinterInfo::default();
+        assert!(only_legacy_minter(&legacy).is_ok());
+
+        let scoped = MinterInfo {
+            proxy: Pubkey::new_unique(),
+            ..Default::default()
+        };
+        assert!(matches!(
+            only_legacy_minter(&scoped).unwrap_err(),
+            anchor_lang::error::Error::AnchorError(err)
+                if err.error_code_number == u32::from(ErrorCode::MinterProxyMismatch)
+        ));
+    }
+
+    #[test]
+    fn test_legacy_minter_info() {
+        let minter = Pubkey::new_unique();
+        let mut data = <MinterInfo as anchor_lang::Discriminator>::discriminator().to_vec();
+        data.extend(minter.to_bytes());
+        data.extend(500_u64.to_le_bytes());
+        data.push(254);
+        assert_eq!(data.len(), 8 + MinterInfo::LEGACY_LEN);
+
+        let mut info = VersionedMinterInfo::try_deserialize(&mut data.as_slice()).unwrap();
+        assert!(info.is_legacy());
+        assert_eq!(info.minter, minter);
+        assert_eq!(info.allowance, 500);
+        assert_eq!(info.proxy, Pubkey::default());
+        assert!(!info.is_rate_limited());
+
+        // statistics are dropped, the legacy fields are kept
+        info.allowance = 400;
+        info.total_minted = 100;
+        info.mint_count = 1;
+        let mut written = vec![];
+        info.try_serialize(&mut written).unwrap();
+        assert_eq!(written.len(), data.len());
+        let info = VersionedMinterInfo::try_deserialize(&mut written.as_slice()).unwrap();
+        assert_eq!(info.allowance, 400);
+        assert_eq!(info.total_minted, 0);
+    }
+
+    #[test]
+    fn test_legacy_minter_info_not_migrated() {
+        let mut data = <MinterInfo as anchor_lang::Discriminator>::discriminator().to_vec();
+        data.resize(8 + MinterInfo::LEGACY_LEN, 0);
+        let info = VersionedMinterInfo::try_deserialize(&mut data.as_slice()).unwrap();
+
+        let mut rate_limited = info.clone();
+        rate_limited.window_length = 100;
+        assert_error(
+            rate_limited.try_serialize(&mut vec![]),
+            ErrorCode::MinterInfoNotMigrated,
+        );
+        let mut paused = info;
+        paused.paused = true;
+        assert_error(
+            paused.try_serialize(&mut vec![]),
+            ErrorCode::MinterInfoNotMigrated,
+        );
+
+        // too short to be a legacy account
+        data.pop();
+        assert!(VersionedMinterInfo::try_deserialize(&mut data.as_slice()).is_err());
+    }
+
+    #[test]
+    fn test_migrated_minter_info() {
+        // migration zero-fills the new fields
+        let mut data = <MinterInfo as anchor_lang::Discriminator>::discriminator().to_vec();
+        data.resize(8 + MinterInfo::LEN, 0);
+        let mut info = VersionedMinterInfo::try_deserialize(&mut data.as_slice()).unwrap();
+        assert!(!info.is_legacy());
+
+        info.window_length = 100;
+        info.total_minted = 100;
+        let mut written = vec![];
+        info.try_serialize(&mut written).unwrap();
+        assert_eq!(written.len(), data.len());
+        let info = VersionedMinterInfo::try_deserialize(&mut written.as_slice()).unwrap();
+        assert_eq!(info.window_length, 100);
+        assert_eq!(info.total_minted, 100);
+    }
+
+    fn assert_error(result: Result<()>, code: ErrorCode) {
+        assert!(matches!(
+            result.unwrap_err(),
+            anchor_lang::error::Error::AnchorError(err)
+                if err.error_code_number == u32::from(code)
+        ));
+    }
+
+    fn config_with_approvers(threshold: u8) -> MintProxyConfig {
+        MintProxyConfig {
+            approvers: vec![Pubkey::new_unique(), Pubkey::new_unique()],
+            approval_threshold: threshold,
+            allowance_increase_limit: 1_000,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_allowance_increase_without_approvers() {
+        let mut config = config_with_approvers(0);
+        config.check_allowance_increase(0, u64::MAX, 0).unwrap();
+        config.check_allowance_increase(0, u64::MAX, 0).unwrap();
+    }
+
+    #[test]
+    fn test_allowance_increase_repeated_updates() {
+        let mut config = config_with_approvers(1);
+        assert_error(
+            config.check_allowance_increase(0, 1_001, 10),
+            ErrorCode::AllowanceIncreaseRequiresApproval,
+        );
+        config.check_allowance_increase(0, 600, 10).unwrap();
+        // raising the same minter again counts towards the same window
+        assert_error(
+            config.check_allowance_increase(600, 1_200, 20),
+            ErrorCode::AllowanceIncreaseRequiresApproval,
+        );
+        // lowering is always allowed
+        config.check_allowance_increase(600, 0, 20).unwrap();
+        // refilling after lowering or minting is an increase too
+        assert_error(
+            config.check_allowance_increase(0, 600, 20),
+            ErrorCode::AllowanceIncreaseRequiresApproval,
+        );
+        config.check_allowance_increase(0, 400, 20).unwrap();
+        assert_eq!(config.allowance_increased_in_window, 1_000);
+    }
+
+    #[test]
+    fn test_allowance_increase_remove_and_add() {
+        let mut config = config_with_approvers(1);
+        // minter_add_v2 is an increase from zero
+        config.check_allowance_increase(0, 1_000, 10).unwrap();
+        // removing and adding the minter again does not reset the window
+        assert_error(
+            config.check_allowance_increase(0, 1_000, 20),
+            ErrorCode::AllowanceIncreaseRequiresApproval,
+        );
+    }
+
+    #[test]
+    fn test_allowance_increase_window() {
+        let mut config = config_with_approvers(1);
+        let start = 1_000_000;
+        config.check_allowance_increase(0, 1_000, start).unwrap();
+        assert_eq!(config.allowance_increase_window_start, start);
+        assert_error(
+            config.check_allowance_increase(0, 1, start + ALLOWANCE_INCREASE_WINDOW - 1),
+            ErrorCode::AllowanceIncreaseRequiresApproval,
+        );
+        config
+            .check_allowance_increase(0, 1_000, start + ALLOWANCE_INCREASE_WINDOW)
+            .unwrap();
+        assert_eq!(
+            config.allowance_increase_window_start,
+            start + ALLOWANCE_INCREASE_WINDOW
+        );
+    }
+
+    #[test]
+    fn test_check_approvals() {
+        let config = config_with_approvers(2);
+        let (a, b) = (config.approvers[0], config.approvers[1]);
+        assert_error(
+            config.check_approvals(&[]),
+            ErrorCode::ApprovalThresholdNotMet,
+        );
+        assert_error(
+            config.check_approvals(&[a]),
+            ErrorCode::ApprovalThresholdNotMet,
+        );
+        // approvals by accounts which are no longer approvers do not count
+        assert_error(
+            config.check_approvals(&[a, Pubkey::new_unique()]),
+            ErrorCode::ApprovalThresholdNotMet,
+        );
+        config.check_approvals(&[a, b]).unwrap();
+    }
+
+    #[test]
+    fn test_set_approvers_requires_approval() {
+        let mut config = config_with_approvers(2);
+        let (a, b) = (config.approvers[0], config.approvers[1]);
+        // the owner alone may neither lower the threshold nor raise the limit
+        assert_error(
+            config.set_approvers(config.approvers.clone(), 0, 1_000, &[]),
+            ErrorCode::ApprovalThresholdNotMet,
+        );
+        assert_error(
+            config.set_approvers(config.approvers.clone(), 2, u64::MAX, &[]),
+            ErrorCode::ApprovalThresholdNotMet,
+        );
+        // nor add approvers which then meet the threshold
+        let (c, d) = (Pubkey::new_unique(), Pubkey::new_unique());
+        assert_error(
+            config.set_approvers(vec![a, b, c, d], 2, 1_000, &[c, d]),
+            ErrorCode::ApprovalThresholdNotMet,
+        );
+        // a repeated signature counts once
+        assert_error(
+            config.set_approvers(vec![a, b], 0, 1_000, &[a, a]),
+            ErrorCode::ApprovalThresholdNotMet,
+        );
+        assert_eq!(config.approval_threshold, 2);
+        assert_eq!(config.allowance_increase_limit, 1_000);
+
+        config.set_approvers(vec![a, b], 0, 1_000, &[a, b]).unwrap();
+        assert_eq!(config.approval_threshold, 0);
+        // without a threshold, the owner configures the approvers alone
+        config.set_approvers(vec![c, d], 1, 500, &[]).unwrap();
+        assert_eq!(config.approvers, vec![c, d]);
+    }
+
+    #[test]
+    fn test_approve_after_rotating_approvers() {
+        let mut config = MintProxyConfig {
+            approvers: (0..MAX_APPROVERS).map(|_| Pubkey::new_unique()).collect(),
+            approval_threshold: MAX_APPROVERS as u8,
+            ..Default::default()
+        };
+        let mut proposal = AllowanceProposal::default();
+        for approver in config.approvers.clone() {
+            proposal.approve(&config, approver).unwrap();
+        }
+        assert_error(
+            proposal.approve(&config, config.approvers[0]),
+            ErrorCode::AlreadyApproved,
+        );
+
+        let rotated: Vec<Pubkey> = (0..MAX_APPROVERS).map(|_| Pubkey::new_unique()).collect();
+        let old = config.approvers.clone();
+        config
+            .set_approvers(rotated.clone(), MAX_APPROVERS as u8, 0, &old)
+            .unwrap();
+        assert_error(proposal.approve(&config, old[0]), ErrorCode::Unauthorized);
+        for approver in rotated.clone() {
+            proposal.approve(&config, approver).unwrap();
+        }
+        assert_eq!(proposal.approvals, rotated);
+        assert!(proposal.try_to_vec().unwrap().len() <= AllowanceProposal::LEN);
+        config.check_approvals(&proposal.approvals).unwrap();
+    }
+
+    #[test]
+    fn test_capacity_bounded_by_allowance() {
+        let info = rate_limited(300);
+        assert_eq!(info.capacity(0).unwrap().remaining, 300);
+    }
 }
@@ -19,10 +19,25 @@ impl<'info> Validate<'info> for CreateRedeemer<'info> {
     }
 }
 
+impl<'info> Validate<'info> for CreateRedeemerWithRate<'info> {
+    fn validate(&self) -> Result<()> {
+        self.create.validate()?;
+        let iou_mint_authority: Option<Pubkey> = self.create.tokens.iou_mint.mint_authority.into();
+        require!(
+            iou_mint_authority == Some(self.iou_mint_authority.key()),
+            Unauthorized
+        );
+        Ok(())
+    }
+}
+
 impl<'info> Validate<'info> for RedeemTokens<'info> {
     fn validate(&self) -> Result<()> {
         self.tokens.validate()?;
         self.tokens.validate_token_accounts(&self.redeemer)?;
+        self.redeemer
+            .check_redeemable(Clock::get()?.unix_timestamp)?;
+        require!(self.redeemer.vesting_duration == 0, RedemptionVests);
 
         assert_keys_eq!(
             self.iou_source.mint,
@@ -31,10 +46,11 @@ impl<'info> Validate<'info> for RedeemTokens<'info> {
         );
 
         require!(self.source_authority.is_signer, Unauthorized);
+        validate_source_authority(&self.iou_source, &self.source_authority)?;
         assert_keys_eq!(
-            self.source_authority,
-            self.redemption_destination.owner,
-            "redemption_destination.owner"
+            self.redemption_destination.mint,
+            self.redeemer.redemption_mint,
+            "redemption_destination.mint"
         );
 
         Ok(())
@@ -66,12 +82,125 @@ impl<'info> Validate<'info> for RedeemTokensFromMintProxy<'info> {
     }
 }
 
-impl<'info> Validate<'info> for ReadonlyTokenPair<'info> {
+impl<'info> Validate<'info> for RedeemTokensFromMintProxyV2<'info> {
     fn validate(&self) -> Result<()> {
-        require!(
-            self.iou_mint.decimals == self.redemption_mint.decimals,
-            DecimalsMismatch
+        self.redeem_ctx.validate()?;
+
+        assert_keys_eq!(
+            self.minter_info.minter,
+            self.redeem_ctx.redeemer,
+            "minter_info.minter"
+        );
+        assert_keys_eq!(
+            self.mint_proxy_config.token_mint,
+            self.redeem_ctx.redeemer.redemption_mint,
+            "redemption_mint"
+        );
+
+        assert_keys_eq!(
+            self.proxy_mint_authority,
+            self.mint_proxy_config.proxy_mint_authority,
+            "proxy_mint_authority"
+        );
+
+        Ok(())
+    }
+}
+
+impl<'info> Validate<'info> for RedeemTokensVested<'info> {
+    fn validate(&self) -> Result<()> {
+        self.tokens.validate()?;
+        self.tokens.validate_token_accounts(&self.redeemer)?;
+        self.redeemer
+            .check_redeemable(Clock::get()?.unix_timestamp)?;
+        require!(self.redeemer.vesting_duration > 0, RedemptionDoesNotVest);
+
+        assert_keys_eq!(
+            self.iou_source.mint,
+            self.redeemer.iou_mint,
+            "iou_source.mint"
+        );
+        validate_source_authority(&self.iou_source, &self.source_authority)?;
+        assert_keys_eq!(self.vesting.redeemer, self.redeemer, "vesting.redeemer");
+        assert_keys_eq!(
+            self.vesting.beneficiary,
+            self.source_authority,
+            "vesting.beneficiary"
+        );
+
+        assert_keys_eq!(self.minter_info.minter, self.redeemer, "minter_info.minter");
+        assert_keys_eq!(
+            self.mint_proxy_config.token_mint,
+            self.redeemer.redemption_mint,
+            "redemption_mint"
         );
+
+        Ok(())
+    }
+}
+
+impl<'info> Validate<'info> for WithdrawVested<'info> {
+    fn validate(&self) -> Result<()> {
+        require!(!self.redeemer.paused, RedeemerPaused);
+        assert_keys_eq!(self.vesting.redeemer, self.redeemer, "vesting.redeemer");
+        assert_keys_eq!(
+            self.vesting.beneficiary,
+            self.beneficiary,
+            "vesting.beneficiary"
+        );
+        assert_keys_eq!(
+            self.redemption_mint,
+            self.redeemer.redemption_mint,
+            "redemption_mint"
+        );
+        assert_keys_eq!(
+            self.destination.mint,
+            self.redeemer.redemption_mint,
+            "destination.mint"
+        );
+
+        assert_keys_eq!(self.minter_info.minter, self.redeemer, "minter_info.minter");
+        assert_keys_eq!(
+            self.mint_proxy_config.token_mint,
+            self.redeemer.redemption_mint,
+            "redemption_mint"
+        );
+        assert_keys_eq!(
+            self.proxy_mint_authority,
+            self.mint_proxy_config.proxy_mint_authority,
+            "proxy_mint_authority"
+        );
+
+        Ok(())
+    }
+}
+
+impl<'info> Validate<'info> for RedeemerAdmin<'info> {
+    fn validate(&self) -> Result<()> {
+        assert_keys_eq!(self.admin, self.redeemer.admin, Unauthorized);
+        Ok(())
+    }
+}
+
+impl<'info> Validate<'info> for Sweep<'info> {
+    fn validate(&self) -> Result<()> {
+        assert_keys_eq!(self.admin, self.redeemer.admin, Unauthorized);
+        assert_keys_eq!(
+            self.redemption_vault,
+            self.redeemer.redemption_vault,
+            "redemption_vault"
+        );
+        assert_keys_eq!(
+            self.treasury.mint,
+            self.redeemer.redemption_mint,
+            "treasury.mint"
+        );
+        Ok(())
+    }
+}
+
+impl<'info> Validate<'info> for ReadonlyTokenPair<'info> {
+    fn validate(&self) -> Result<()> {
         assert_keys_eq!(self.redemption_vault.mint, self.redemption_mint);
 
         Ok(())
@@ -85,3 +214,12 @@ impl<'info> Validate<'info> for MutTokenPair<'info> {
         Ok(())
     }
 }
+
+/// Ensures the `authority` is the owner or a delegate of the `source`.
+fn validate_source_authority(source: &TokenAccount, authority: &Signer) -> Result<()> {
+    if source.owner != authority.key() {
+        let delegate: Option<Pubkey> = source.delegate.into();
+        require!(delegate == Some(authority.key()), Unauthorized);
+    }
+    Ok(())
+}
@@ -4,11 +4,14 @@
 #![allow(deprecated)]
 
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program;
 use anchor_lang::{accounts::cpi_state::CpiState, solana_program::pubkey::PUBKEY_BYTES};
 use anchor_spl::token::{self, Mint, Token, TokenAccount};
+use continuation_router::ActionType;
 use mint_proxy::mint_proxy::MintProxy;
-use mint_proxy::MinterInfo;
+use mint_proxy::{MintProxyConfig, VersionedMinterInfo};
 use vipers::prelude::*;
+use vipers::program_err;
 
 mod account_validators;
 mod macros;
@@ -21,43 +24,158 @@ declare_id!("RDM23yr8pr1kEAmhnFpaabPny6C9UVcEcok3Py5v86X");
 pub mod redeemer {
     use super::*;
 
-    /// Initializes the [Redeemer].
+    /// Initializes the [Redeemer], redeeming IOU tokens 1:1.
     #[access_control(ctx.accounts.validate())]
     pub fn create_redeemer(ctx: Context<CreateRedeemer>, _bump: u8) -> Result<()> {
         let tokens = &ctx.accounts.tokens;
+        require!(
+            tokens.iou_mint.decimals == tokens.redemption_mint.decimals,
+            DecimalsMismatch
+        );
+        ctx.accounts.init_redeemer(
+            *unwrap_int!(ctx.bumps.get("redeemer")),
+            1,
+            1,
+            Pubkey::default(),
+        )
+    }
+
+    /// Initializes the [Redeemer] with an exchange rate of `numerator / denominator`
+    /// redemption tokens per IOU token.
+    ///
+    /// The rate applies to raw token amounts, so it also accounts for any
+    /// difference in decimals between the two mints.
+    /// The `admin` may restrict redemptions to a window, pause redemptions,
+    /// and sweep the vault once the window has closed.
+    ///
+    /// Only the mint authority of the IOU token may choose the rate and the admin
+    /// of the [Redeemer] of a pair.
+    #[access_control(ctx.accounts.validate())]
+    pub fn create_redeemer_with_rate(
+        ctx: Context<CreateRedeemerWithRate>,
+        numerator: u64,
+        denominator: u64,
+        admin: Pubkey,
+    ) -> Result<()> {
+        require!(numerator > 0 && denominator > 0, InvalidExchangeRate);
+        ctx.accounts.create.init_redeemer(
+            *unwrap_int!(ctx.bumps.get("redeemer")),
+            numerator,
+            denominator,
+            admin,
+        )
+    }
+
+    /// Grows a [Redeemer] created with an older layout to [Redeemer::LEN].
+    ///
+    /// Migrated redeemers redeem 1:1. Anyone may migrate a [Redeemer];
+    /// the payer funds the additional rent.
+    pub fn migrate_redeemer(ctx: Context<MigrateRedeemer>) -> Result<()> {
+        ctx.accounts.migrate()
+    }
+
+    /// Sets the [Redeemer::admin].
+    #[access_control(ctx.accounts.validate())]
+    pub fn set_admin(ctx: Context<RedeemerAdmin>, new_admin: Pubkey) -> Result<()> {
         let redeemer = &mut ctx.accounts.redeemer;
-        redeemer.bump = *unwrap_int!(ctx.bumps.get("redeemer"));
-        redeemer.iou_mint = tokens.iou_mint.key();
-        redeemer.redemption_mint = tokens.redemption_mint.key();
-        redeemer.redemption_vault = tokens.redemption_vault.key();
+        let previous_admin = redeemer.admin;
+        redeemer.admin = new_admin;
 
+        emit!(SetAdminEvent {
+            redeemer: redeemer.key(),
+            previous_admin,
+            admin: new_admin,
+        });
+        Ok(())
+    }
+
+    /// Sets the times between which tokens may be redeemed.
+    /// An `end_ts` of zero means redemptions never end.
+    ///
+    /// An open window may only be shortened such that tokens remain redeemable
+    /// for at least [REDEMPTION_WINDOW_NOTICE].
+    #[access_control(ctx.accounts.validate())]
+    pub fn set_redemption_window(
+        ctx: Context<RedeemerAdmin>,
+        start_ts: i64,
+        end_ts: i64,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        ctx.accounts
+            .redeemer
+            .check_redemption_window(start_ts, end_ts, now)?;
+        let redeemer = &mut ctx.accounts.redeemer;
+        redeemer.start_ts = start_ts;
+        redeemer.end_ts = end_ts;
+
+        emit!(SetRedemptionWindowEvent {
+            redeemer: redeemer.key(),
+            start_ts,
+            end_ts,
+        });
+        Ok(())
+    }
+
+    /// Pauses or unpauses redemptions.
+    ///
+    /// Time spent paused does not count towards the [REDEMPTION_WINDOW_NOTICE]:
+    /// see [Redeemer::set_paused].
+    #[access_control(ctx.accounts.validate())]
+    pub fn set_paused(ctx: Context<RedeemerAdmin>, paused: bool) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let redeemer = &mut ctx.accounts.redeemer;
+        unwrap_int!(redeemer.set_paused(paused, now));
+
+        emit!(SetPausedEvent {
+            redeemer: redeemer.key(),
+            paused,
+            end_ts: redeemer.end_ts,
+        });
+        Ok(())
+    }
+
+    /// Transfers all tokens left in the redemption vault to a treasury
+    /// once the redemption window has closed.
+    #[access_control(ctx.accounts.validate())]
+    pub fn sweep(ctx: Context<Sweep>) -> Result<()> {
+        let redeemer = &ctx.accounts.redeemer;
+        redeemer.check_sweepable(Clock::get()?.unix_timestamp)?;
+
+        let amount = ctx.accounts.redemption_vault.amount;
+        let seeds = gen_redeemer_signer_seeds!(redeemer);
+        let signer_seeds = &[&seeds[..]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.redemption_vault.to_account_info(),
+                    to: ctx.accounts.treasury.to_account_info(),
+                    authority: redeemer.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+        )?;
+
+        emit!(SweepEvent {
+            redeemer: redeemer.key(),
+            treasury: ctx.accounts.treasury.key(),
+            amount,
+        });
         Ok(())
     }
 
     /// Redeems some of a user's tokens from the redemption vault.
     #[access_control(ctx.accounts.validate())]
     pub fn redeem_tokens(ctx: Context<RedeemTokens>, amount: u64) -> Result<()> {
+        let redemption_amount = ctx.accounts.redeemer.checked_redemption_amount(amount)?;
         ctx.accounts.tokens.burn_iou_tokens(
             ctx.accounts.iou_source.to_account_info(),
             ctx.accounts.source_authority.to_account_info(),
             amount,
         )?;
 
-        let cpi_accounts = token::Transfer {
-            from: ctx.accounts.tokens.redemption_vault.to_account_info(),
-            to: ctx.accounts.redemption_destination.to_account_info(),
-            authority: ctx.accounts.redeemer.to_account_info(),
-        };
-
-        let seeds = gen_redeemer_signer_seeds!(ctx.accounts.redeemer);
-        let signer_seeds = &[&seeds[..]];
-
-        let cpi_ctx = CpiContext::new_with_signer(
-            ctx.accounts.tokens.token_program.to_account_info(),
-            cpi_accounts,
-            signer_seeds,
-        );
-        token::transfer(cpi_ctx, amount)?;
+        ctx.accounts.transfer_from_vault(redemption_amount)?;
 
         let redeemer = &ctx.accounts.redeemer;
         emit!(RedeemTokensEvent {
@@ -65,6 +183,8 @@ pub mod redeemer {
             iou_mint: redeemer.iou_mint,
             destination_mint: redeemer.redemption_mint,
             amount,
+            redemption_amount,
+            recipient: ctx.accounts.redemption_destination.key(),
         });
 
         Ok(())
@@ -76,6 +196,11 @@ pub mod redeemer {
         amount: u64,
     ) -> Result<()> {
         ctx.accounts.validate()?;
+        let redemption_amount = ctx
+            .accounts
+            .redeem_ctx
+            .redeemer
+            .checked_redemption_amount(amount)?;
         ctx.accounts.redeem_ctx.tokens.burn_iou_tokens(
             ctx.accounts.redeem_ctx.iou_source.to_account_info(),
             ctx.accounts.redeem_ctx.source_authority.to_account_info(),
@@ -112,7 +237,7 @@ pub mod redeemer {
         mint_proxy::invoke_perform_mint(
             CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds),
             ctx.accounts.mint_proxy_state.to_account_info(),
-            amount,
+            redemption_amount,
         )?;
 
         emit!(RedeemTokensEvent {
@@ -120,6 +245,8 @@ pub mod redeemer {
             iou_mint: redeemer.iou_mint,
             destination_mint: redeemer.redemption_mint,
             amount,
+            redemption_amount,
+            recipient: ctx.accounts.redeem_ctx.redemption_destination.key(),
         });
 
         Ok(())
@@ -129,15 +256,253 @@ pub mod redeemer {
     pub fn redeem_all_tokens_from_mint_proxy(
         ctx: Context<RedeemTokensFromMintProxy>,
     ) -> Result<()> {
-        let amount = ctx.accounts.redeem_ctx.iou_source.amount;
+        let amount = ctx.accounts.redeem_ctx.redeemable_iou_amount();
         redeem_tokens_from_mint_proxy(ctx, amount)
     }
+
+    /// Redeems an amount of a user's tokens against the [mint_proxy::MintProxyConfig].
+    #[access_control(ctx.accounts.validate())]
+    pub fn redeem_tokens_from_mint_proxy_v2(
+        ctx: Context<RedeemTokensFromMintProxyV2>,
+        amount: u64,
+    ) -> Result<()> {
+        let redeem_ctx = &ctx.accounts.redeem_ctx;
+        let redemption_amount = redeem_ctx.redeemer.checked_redemption_amount(amount)?;
+        redeem_ctx.tokens.burn_iou_tokens(
+            redeem_ctx.iou_source.to_account_info(),
+            redeem_ctx.source_authority.to_account_info(),
+            amount,
+        )?;
+
+        ctx.accounts.mint_redemption_tokens(redemption_amount)?;
+
+        let redeemer = &redeem_ctx.redeemer;
+        emit!(RedeemTokensEvent {
+            user: *redeem_ctx.source_authority.key,
+            iou_mint: redeemer.iou_mint,
+            destination_mint: redeemer.redemption_mint,
+            amount,
+            redemption_amount,
+            recipient: redeem_ctx.redemption_destination.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Redeems all of a user's tokens against the [mint_proxy::MintProxyConfig].
+    pub fn redeem_all_tokens_from_mint_proxy_v2(
+        ctx: Context<RedeemTokensFromMintProxyV2>,
+    ) -> Result<()> {
+        let amount = ctx.accounts.redeem_ctx.redeemable_iou_amount();
+        redeem_tokens_from_mint_proxy_v2(ctx, amount)
+    }
+
+    /// Sets whether hybrid redemptions mint before drawing from the redemption vault.
+    #[access_control(ctx.accounts.validate())]
+    pub fn set_mint_first(ctx: Context<RedeemerAdmin>, mint_first: bool) -> Result<()> {
+        let redeemer = &mut ctx.accounts.redeemer;
+        redeemer.mint_first = mint_first;
+
+        emit!(SetMintFirstEvent {
+            redeemer: redeemer.key(),
+            mint_first,
+        });
+        Ok(())
+    }
+
+    /// Redeems an amount of a user's tokens from both the redemption vault and the
+    /// [mint_proxy::MintProxyConfig].
+    ///
+    /// Tokens are drawn from the vault first and the remainder is minted,
+    /// or the reverse if [Redeemer::mint_first] is set.
+    #[access_control(ctx.accounts.validate())]
+    pub fn redeem_tokens_hybrid(
+        ctx: Context<RedeemTokensFromMintProxyV2>,
+        amount: u64,
+    ) -> Result<()> {
+        let redeem_ctx = &ctx.accounts.redeem_ctx;
+        let redemption_amount = redeem_ctx.redeemer.checked_redemption_amount(amount)?;
+        let now = Clock::get()?.unix_timestamp;
+        let (vault_amount, mint_amount) = redeem_ctx.redeemer.split_hybrid_redemption(
+            redemption_amount,
+            redeem_ctx.tokens.redemption_vault.amount,
+            ctx.accounts.mint_capacity(now),
+        );
+        redeem_ctx.tokens.burn_iou_tokens(
+            redeem_ctx.iou_source.to_account_info(),
+            redeem_ctx.source_authority.to_account_info(),
+            amount,
+        )?;
+
+        if vault_amount > 0 {
+            redeem_ctx.transfer_from_vault(vault_amount)?;
+        }
+        if mint_amount > 0 {
+            ctx.accounts.mint_redemption_tokens(mint_amount)?;
+        }
+
+        let redeemer = &redeem_ctx.redeemer;
+        emit!(RedeemTokensEvent {
+            user: *redeem_ctx.source_authority.key,
+            iou_mint: redeemer.iou_mint,
+            destination_mint: redeemer.redemption_mint,
+            amount,
+            redemption_amount,
+            recipient: redeem_ctx.redemption_destination.key(),
+        });
+        emit!(RedeemTokensHybridEvent {
+            user: *redeem_ctx.source_authority.key,
+            vault_amount,
+            mint_amount,
+        });
+
+        Ok(())
+    }
+
+    /// Redeems all of a user's tokens from both the redemption vault and the
+    /// [mint_proxy::MintProxyConfig].
+    pub fn redeem_all_tokens_hybrid(ctx: Context<RedeemTokensFromMintProxyV2>) -> Result<()> {
+        let amount = ctx.accounts.redeem_ctx.redeemable_iou_amount();
+        redeem_tokens_hybrid(ctx, amount)
+    }
+
+    /// Processes an action routed through the [continuation_router].
+    ///
+    /// [ActionType::RDRedeem] performs a [redeemer::redeem_tokens_hybrid].
+    pub fn process_router_action(
+        ctx: Context<RedeemTokensFromMintProxyV2>,
+        action: u16,
+        amount_in: u64,
+        minimum_amount_out: u64,
+    ) -> Result<()> {
+        let action_type = try_or_err!(ActionType::try_from(action), UnknownAction);
+        msg!("Router action received: {:?}", action_type);
+        match action_type {
+            ActionType::RDRedeem => {
+                let redemption_amount = ctx
+                    .accounts
+                    .redeem_ctx
+                    .redeemer
+                    .checked_redemption_amount(amount_in)?;
+                require!(redemption_amount >= minimum_amount_out, MinimumOutNotMet);
+                redeem_tokens_hybrid(ctx, amount_in)
+            }
+            _ => program_err!(UnknownAction),
+        }
+    }
+
+    /// Sets the duration over which redeemed tokens vest.
+    /// If non-zero, tokens may only be redeemed via [redeemer::redeem_tokens_vested].
+    #[access_control(ctx.accounts.validate())]
+    pub fn set_vesting_duration(ctx: Context<RedeemerAdmin>, vesting_duration: i64) -> Result<()> {
+        require!(vesting_duration >= 0, InvalidVestingDuration);
+        let redeemer = &mut ctx.accounts.redeemer;
+        redeemer.vesting_duration = vesting_duration;
+
+        emit!(SetVestingDurationEvent {
+            redeemer: redeemer.key(),
+            vesting_duration,
+        });
+        Ok(())
+    }
+
+    /// Creates a [RedemptionVesting] for a beneficiary.
+    pub fn create_redemption_vesting(ctx: Context<CreateRedemptionVesting>) -> Result<()> {
+        let vesting = &mut ctx.accounts.vesting;
+        vesting.redeemer = ctx.accounts.redeemer.key();
+        vesting.beneficiary = ctx.accounts.beneficiary.key();
+        vesting.bump = *unwrap_int!(ctx.bumps.get("vesting"));
+        Ok(())
+    }
+
+    /// Redeems some of a user's tokens into their [RedemptionVesting].
+    ///
+    /// The redeemed tokens, together with any tokens of the [RedemptionVesting]
+    /// which have not vested yet, vest linearly over the [Redeemer::vesting_duration].
+    ///
+    /// Fails if the [Redeemer] may not currently mint the redeemed amount.
+    #[access_control(ctx.accounts.validate())]
+    pub fn redeem_tokens_vested(ctx: Context<RedeemTokensVested>, amount: u64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let redemption_amount = ctx.accounts.redeemer.checked_redemption_amount(amount)?;
+        require!(
+            mint_capacity(
+                &ctx.accounts.mint_proxy_config,
+                &ctx.accounts.minter_info,
+                ctx.accounts.tokens.redemption_mint.supply,
+                now,
+            ) >= redemption_amount,
+            InsufficientMintCapacity
+        );
+        ctx.accounts.tokens.burn_iou_tokens(
+            ctx.accounts.iou_source.to_account_info(),
+            ctx.accounts.source_authority.to_account_info(),
+            amount,
+        )?;
+
+        let vesting_duration = ctx.accounts.redeemer.vesting_duration;
+        let vesting = &mut ctx.accounts.vesting;
+        unwrap_int!(vesting.top_up(redemption_amount, now, vesting_duration));
+
+        let redeemer = &ctx.accounts.redeemer;
+        emit!(RedeemTokensVestedEvent {
+            user: ctx.accounts.source_authority.key(),
+            iou_mint: redeemer.iou_mint,
+            destination_mint: redeemer.redemption_mint,
+            amount,
+            redemption_amount,
+            beneficiary: vesting.beneficiary,
+            end_ts: vesting.end_ts,
+        });
+        Ok(())
+    }
+
+    /// Withdraws all vested tokens of a [RedemptionVesting], minting them via the
+    /// [mint_proxy::MintProxyConfig].
+    #[access_control(ctx.accounts.validate())]
+    pub fn withdraw_vested(ctx: Context<WithdrawVested>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let amount = unwrap_int!(ctx.accounts.vesting.withdraw(now));
+
+        let redeemer = &ctx.accounts.redeemer;
+        let seeds = gen_redeemer_signer_seeds!(redeemer);
+        let signer_seeds = &[&seeds[..]];
+        mint_proxy::cpi::perform_mint_v2(
+            CpiContext::new_with_signer(
+                ctx.accounts.mint_proxy_program.to_account_info(),
+                mint_proxy::cpi::accounts::PerformMintV2 {
+                    config: ctx.accounts.mint_proxy_config.to_account_info(),
+                    inner: mint_proxy::cpi::accounts::PerformMint {
+                        proxy_mint_authority: ctx.accounts.proxy_mint_authority.to_account_info(),
+                        minter: redeemer.to_account_info(),
+                        token_mint: ctx.accounts.redemption_mint.to_account_info(),
+                        destination: ctx.accounts.destination.to_account_info(),
+                        minter_info: ctx.accounts.minter_info.to_account_info(),
+                        token_program: ctx.accounts.token_program.to_account_info(),
+                    },
+                },
+                signer_seeds,
+            ),
+            amount,
+        )?;
+
+        emit!(WithdrawVestedEvent {
+            beneficiary: ctx.accounts.beneficiary.key(),
+            redeemer: redeemer.key(),
+            amount,
+        });
+        Ok(())
+    }
 }
 
 // --------------------------------
 // Accounts
 // --------------------------------
 
+/// Minimum time for which an open redemption window stays open once it is shortened,
+/// so that users may redeem before the remaining tokens are swept.
+pub const REDEMPTION_WINDOW_NOTICE: i64 = 7 * 24 * 60 * 60;
+
 /// Allows redeeming the [Redeemer::iou_mint] for the [Redeemer::redemption_mint].
 #[account]
 #[derive(Default)]
@@ -150,10 +515,211 @@ pub struct Redeemer {
     pub redemption_mint: Pubkey,
     /// ...
     pub redemption_vault: Pubkey,
+    /// Numerator of the amount of redemption tokens received per IOU token.
+    pub exchange_rate_numerator: u64,
+    /// Denominator of the amount of redemption tokens received per IOU token.
+    pub exchange_rate_denominator: u64,
+    /// Account which may pause redemptions, set the redemption window and sweep the vault.
+    /// If unset, the redeemer has no admin and redeems forever.
+    pub admin: Pubkey,
+    /// Time from which tokens may be redeemed.
+    pub start_ts: i64,
+    /// Time from which tokens may no longer be redeemed. Zero if redemptions never end.
+    pub end_ts: i64,
+    /// If true, tokens may not be redeemed.
+    pub paused: bool,
+    /// Duration over which redeemed tokens vest. Zero if redeemed tokens are liquid.
+    pub vesting_duration: i64,
+    /// If true, hybrid redemptions mint before drawing from the redemption vault.
+    pub mint_first: bool,
+    /// Time at which redemptions were paused, if [Redeemer::paused].
+    pub paused_ts: i64,
 }
 
 impl Redeemer {
-    pub const LEN: usize = 1 + PUBKEY_BYTES * 3;
+    pub const LEN: usize = 1 + PUBKEY_BYTES * 3 + 8 + 8 + PUBKEY_BYTES + 8 + 8 + 1 + 8 + 1 + 8;
+
+    /// Splits a hybrid redemption of `redemption_amount` tokens into the amounts
+    /// drawn from the vault and minted, in the order given by [Redeemer::mint_first].
+    ///
+    /// If both sources together cannot cover the redemption, the second source
+    /// is asked for the remainder, so the redemption fails.
+    pub fn split_hybrid_redemption(
+        &self,
+        redemption_amount: u64,
+        vault_balance: u64,
+        mint_capacity: u64,
+    ) -> (u64, u64) {
+        if self.mint_first {
+            let mint_amount = std::cmp::min(redemption_amount, mint_capacity);
+            (redemption_amount - mint_amount, mint_amount)
+        } else {
+            let vault_amount = std::cmp::min(redemption_amount, vault_balance);
+            (vault_amount, redemption_amount - vault_amount)
+        }
+    }
+
+    /// Ensures tokens may be redeemed at `now`.
+    pub fn check_redeemable(&self, now: i64) -> Result<()> {
+        require!(!self.paused, RedeemerPaused);
+        require!(now >= self.start_ts, RedemptionNotStarted);
+        require!(self.end_ts == 0 || now < self.end_ts, RedemptionEnded);
+        Ok(())
+    }
+
+    /// Pauses or unpauses redemptions at `now`.
+    ///
+    /// Unpausing keeps the redemption window open for as much of the
+    /// [REDEMPTION_WINDOW_NOTICE] as was left when redemptions were paused,
+    /// so that the notice cannot run out while redemptions are paused.
+    /// Returns [None] on overflow.
+    pub fn set_paused(&mut self, paused: bool, now: i64) -> Option<()> {
+        if paused && !self.paused {
+            self.paused_ts = now;
+        }
+        if !paused && self.paused && self.end_ts != 0 {
+            let open_from = std::cmp::max(self.paused_ts, self.start_ts);
+            if open_from < self.end_ts {
+                let notice_left = std::cmp::min(
+                    self.end_ts.checked_sub(open_from)?,
+                    REDEMPTION_WINDOW_NOTICE,
+                );
+                self.end_ts = std::cmp::max(self.end_ts, now.checked_add(notice_left)?);
+            }
+        }
+        self.paused = paused;
+        Some(())
+    }
+
+    /// Ensures the redemption vault may be swept at `now`.
+    ///
+    /// The window must have closed, and must not have been paused before it
+    /// closed, since unpausing would reopen it.
+    pub fn check_sweepable(&self, now: i64) -> Result<()> {
+        require!(self.end_ts != 0 && now >= self.end_ts, RedemptionWindowOpen);
+        require!(
+            !self.paused || self.paused_ts >= self.end_ts,
+            RedemptionWindowOpen
+        );
+        Ok(())
+    }
+
+    /// Ensures the redemption window may be set to `start_ts` and `end_ts` at `now`.
+    ///
+    /// If the window is open, the new window must keep it open from `now` until
+    /// [REDEMPTION_WINDOW_NOTICE] from now, or until its current end if that is sooner.
+    pub fn check_redemption_window(&self, start_ts: i64, end_ts: i64, now: i64) -> Result<()> {
+        require!(end_ts == 0 || end_ts > start_ts, InvalidRedemptionWindow);
+        let is_open = now >= self.start_ts && (self.end_ts == 0 || now < self.end_ts);
+        if is_open {
+            let notice_end = now.saturating_add(REDEMPTION_WINDOW_NOTICE);
+            let open_until = if self.end_ts == 0 {
+                notice_end
+            } else {
+                std::cmp::min(self.end_ts, notice_end)
+            };
+            require!(
+                start_ts <= now && (end_ts == 0 || end_ts >= open_until),
+                RedemptionNoticeTooShort
+            );
+        }
+        Ok(())
+    }
+
+    /// Computes the amount of redemption tokens received for `iou_amount` IOU tokens.
+    ///
+    /// Rounds down, so the redeemer never pays out more than the exchange rate.
+    /// Returns [None] on overflow or if the exchange rate is not set.
+    pub fn redemption_amount(&self, iou_amount: u64) -> Option<u64> {
+        if self.exchange_rate_denominator == 0 {
+            return None;
+        }
+        let amount = (iou_amount as u128)
+            .checked_mul(self.exchange_rate_numerator.into())?
+            .checked_div(self.exchange_rate_denominator.into())?;
+        amount.try_into().ok()
+    }
+
+    /// Computes the amount of redemption tokens received for `iou_amount` IOU tokens,
+    /// failing if a non-zero amount of IOU tokens would redeem nothing.
+    pub fn checked_redemption_amount(&self, iou_amount: u64) -> Result<u64> {
+        let redemption_amount = unwrap_int!(self.redemption_amount(iou_amount));
+        require!(
+            redemption_amount > 0 || iou_amount == 0,
+            RedemptionAmountTooSmall
+        );
+        Ok(redemption_amount)
+    }
+}
+
+/// Redeemed tokens vesting to a beneficiary.
+///
+/// Vesting follows the [lockup::calculator] semantics of a [lockup::Release].
+#[account]
+#[derive(Default)]
+pub struct RedemptionVesting {
+    /// The [Redeemer].
+    pub redeemer: Pubkey,
+    /// Beneficiary of the vested tokens.
+    pub beneficiary: Pubkey,
+    /// Bump seed.
+    pub bump: u8,
+    /// Amount of tokens vesting in the current schedule.
+    pub start_balance: u64,
+    /// Amount of tokens of the current schedule which have not been withdrawn.
+    pub outstanding: u64,
+    /// Tokens vested in previous schedules which have not been withdrawn.
+    pub claimable: u64,
+    /// The time at which the current schedule begins.
+    pub start_ts: i64,
+    /// The time at which all tokens of the current schedule have vested.
+    pub end_ts: i64,
+}
+
+impl RedemptionVesting {
+    pub const LEN: usize = PUBKEY_BYTES * 2 + 1 + 8 * 5;
+
+    /// The current schedule as a [lockup::Release].
+    fn release(&self) -> lockup::Release {
+        let mut release = lockup::Release::default();
+        release.beneficiary = self.beneficiary;
+        release.start_balance = self.start_balance;
+        release.outstanding = self.outstanding;
+        release.start_ts = self.start_ts;
+        release.end_ts = self.end_ts;
+        release
+    }
+
+    /// Amount of tokens which may be withdrawn at `now`.
+    pub fn available_for_withdrawal(&self, now: i64) -> Option<u64> {
+        let vested = lockup::calculator::available_for_withdrawal(&self.release(), now).ok()?;
+        self.claimable.checked_add(vested)
+    }
+
+    /// Adds `amount` tokens, restarting the schedule at `now`.
+    ///
+    /// Tokens which have vested are kept as [RedemptionVesting::claimable];
+    /// tokens which have not vested yet vest again, together with `amount`,
+    /// over `vesting_duration`.
+    pub fn top_up(&mut self, amount: u64, now: i64, vesting_duration: i64) -> Option<()> {
+        let vested = lockup::calculator::available_for_withdrawal(&self.release(), now).ok()?;
+        let unvested = self.outstanding.checked_sub(vested)?;
+        self.claimable = self.claimable.checked_add(vested)?;
+        self.start_balance = unvested.checked_add(amount)?;
+        self.outstanding = self.start_balance;
+        self.start_ts = now;
+        self.end_ts = now.checked_add(vesting_duration)?;
+        Some(())
+    }
+
+    /// Withdraws all tokens available at `now`, returning the amount withdrawn.
+    pub fn withdraw(&mut self, now: i64) -> Option<u64> {
+        let vested = lockup::calculator::available_for_withdrawal(&self.release(), now).ok()?;
+        let amount = self.claimable.checked_add(vested)?;
+        self.outstanding = self.outstanding.checked_sub(vested)?;
+        self.claimable = 0;
+        Some(amount)
+    }
 }
 
 // --------------------------------
@@ -212,6 +778,294 @@ pub struct CreateRedeemer<'info> {
     pub system_program: Program<'info, System>,
 }
 
+/// Accounts for [redeemer::create_redeemer_with_rate].
+#[derive(Accounts)]
+pub struct CreateRedeemerWithRate<'info> {
+    /// Creates the [Redeemer].
+    pub create: CreateRedeemer<'info>,
+    /// Mint authority of the IOU token.
+    pub iou_mint_authority: Signer<'info>,
+}
+
+impl<'info> CreateRedeemer<'info> {
+    fn init_redeemer(
+        &mut self,
+        bump: u8,
+        numerator: u64,
+        denominator: u64,
+        admin: Pubkey,
+    ) -> Result<()> {
+        let redeemer = &mut self.redeemer;
+        redeemer.bump = bump;
+        redeemer.iou_mint = self.tokens.iou_mint.key();
+        redeemer.redemption_mint = self.tokens.redemption_mint.key();
+        redeemer.redemption_vault = self.tokens.redemption_vault.key();
+        redeemer.exchange_rate_numerator = numerator;
+        redeemer.exchange_rate_denominator = denominator;
+        redeemer.admin = admin;
+
+        emit!(CreateRedeemerEvent {
+            redeemer: redeemer.key(),
+            iou_mint: redeemer.iou_mint,
+            redemption_mint: redeemer.redemption_mint,
+            exchange_rate_numerator: numerator,
+            exchange_rate_denominator: denominator,
+            admin,
+        });
+        Ok(())
+    }
+}
+
+/// Accounts for [redeemer::migrate_redeemer].
+#[derive(Accounts)]
+pub struct MigrateRedeemer<'info> {
+    /// The [Redeemer] to migrate.
+    /// CHECK: May not deserialize before migration. The discriminator is checked.
+    #[account(mut, owner = crate::ID)]
+    pub redeemer: UncheckedAccount<'info>,
+    /// Payer of the additional rent.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// System program.
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateRedeemer<'info> {
+    fn migrate(&self) -> Result<()> {
+        let info = self.redeemer.to_account_info();
+        let new_len = 8 + Redeemer::LEN;
+        {
+            let data = info.try_borrow_data()?;
+            invariant!(
+                data.len() >= 8
+                    && data[..8] == <Redeemer as anchor_lang::Discriminator>::discriminator(),
+                "redeemer discriminator"
+            );
+            if data.len() >= new_len {
+                return Ok(());
+            }
+        }
+
+        let rent = Rent::get()?
+            .minimum_balance(new_len)
+            .saturating_sub(info.lamports());
+        if rent > 0 {
+            solana_program::program::invoke(
+                &solana_program::system_instruction::transfer(self.payer.key, info.key, rent),
+                &[
+                    self.payer.to_account_info(),
+                    info.clone(),
+                    self.system_program.to_account_info(),
+                ],
+            )?;
+        }
+        info.realloc(new_len, true)?;
+
+        let mut redeemer: Account<Redeemer> = Account::try_from(&info)?;
+        if redeemer.exchange_rate_denominator == 0 {
+            redeemer.exchange_rate_numerator = 1;
+            redeemer.exchange_rate_denominator = 1;
+        }
+        redeemer.exit(&crate::ID)
+    }
+}
+
+impl<'info> RedeemTokens<'info> {
+    /// Amount of IOU tokens the [RedeemTokens::source_authority] may redeem.
+    fn redeemable_iou_amount(&self) -> u64 {
+        if self.iou_source.owner == self.source_authority.key() {
+            self.iou_source.amount
+        } else {
+            std::cmp::min(self.iou_source.amount, self.iou_source.delegated_amount)
+        }
+    }
+
+    /// Transfers redemption tokens from the vault to the [RedeemTokens::redemption_destination].
+    fn transfer_from_vault(&self, amount: u64) -> Result<()> {
+        let seeds = gen_redeemer_signer_seeds!(self.redeemer);
+        let signer_seeds = &[&seeds[..]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                self.tokens.token_program.to_account_info(),
+                token::Transfer {
+                    from: self.tokens.redemption_vault.to_account_info(),
+                    to: self.redemption_destination.to_account_info(),
+                    authority: self.redeemer.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+        )
+    }
+}
+
+/// Amount of redemption tokens a minter may currently mint, given the
+/// `supply` of the redemption mint.
+fn mint_capacity(
+    config: &MintProxyConfig,
+    minter_info: &VersionedMinterInfo,
+    supply: u64,
+    now: i64,
+) -> u64 {
+    if config.paused || minter_info.paused {
+        return 0;
+    }
+    let remaining = minter_info
+        .capacity(now)
+        .map(|capacity| capacity.remaining)
+        .unwrap_or(0);
+    let supply_headroom = config
+        .supply_cap(now)
+        .and_then(|cap| cap.checked_sub(supply))
+        .unwrap_or(0);
+    std::cmp::min(remaining, supply_headroom)
+}
+
+impl<'info> RedeemTokensFromMintProxyV2<'info> {
+    /// Amount of redemption tokens the [Redeemer] may currently mint.
+    fn mint_capacity(&self, now: i64) -> u64 {
+        mint_capacity(
+            &self.mint_proxy_config,
+            &self.minter_info,
+            self.redeem_ctx.tokens.redemption_mint.supply,
+            now,
+        )
+    }
+
+    /// Mints redemption tokens to the [RedeemTokens::redemption_destination].
+    fn mint_redemption_tokens(&self, amount: u64) -> Result<()> {
+        let redeem_ctx = &self.redeem_ctx;
+        let redeemer = &redeem_ctx.redeemer;
+        let seeds = gen_redeemer_signer_seeds!(redeemer);
+        let signer_seeds = &[&seeds[..]];
+        mint_proxy::cpi::perform_mint_v2(
+            CpiContext::new_with_signer(
+                self.mint_proxy_program.to_account_info(),
+                mint_proxy::cpi::accounts::PerformMintV2 {
+                    config: self.mint_proxy_config.to_account_info(),
+                    inner: mint_proxy::cpi::accounts::PerformMint {
+                        proxy_mint_authority: self.proxy_mint_authority.to_account_info(),
+                        minter: redeemer.to_account_info(),
+                        token_mint: redeem_ctx.tokens.redemption_mint.to_account_info(),
+                        destination: redeem_ctx.redemption_destination.to_account_info(),
+                        minter_info: self.minter_info.to_account_info(),
+                        token_program: redeem_ctx.tokens.token_program.to_account_info(),
+                    },
+                },
+                signer_seeds,
+            ),
+            amount,
+        )
+    }
+}
+
+/// Accounts for [redeemer::set_admin], [redeemer::set_redemption_window] and [redeemer::set_paused].
+#[derive(Accounts)]
+pub struct RedeemerAdmin<'info> {
+    /// Redeemer PDA.
+    #[account(mut)]
+    pub redeemer: Account<'info, Redeemer>,
+    /// The [Redeemer::admin].
+    pub admin: Signer<'info>,
+}
+
+/// Accounts for [redeemer::sweep].
+#[derive(Accounts)]
+pub struct Sweep<'info> {
+    /// Redeemer PDA.
+    pub redeemer: Account<'info, Redeemer>,
+    /// The [Redeemer::admin].
+    pub admin: Signer<'info>,
+    /// Vault of the redemption token.
+    #[account(mut)]
+    pub redemption_vault: Account<'info, TokenAccount>,
+    /// Token account receiving the leftover redemption tokens.
+    #[account(mut)]
+    pub treasury: Account<'info, TokenAccount>,
+    /// The spl_token program.
+    pub token_program: Program<'info, Token>,
+}
+
+/// Accounts for [redeemer::create_redemption_vesting].
+#[derive(Accounts)]
+pub struct CreateRedemptionVesting<'info> {
+    /// Redeemer PDA.
+    pub redeemer: Account<'info, Redeemer>,
+    /// Beneficiary of the vested tokens.
+    /// CHECK: Arbitrary.
+    pub beneficiary: UncheckedAccount<'info>,
+    /// The [RedemptionVesting].
+    #[account(
+        init,
+        seeds = [
+            b"RedemptionVesting".as_ref(),
+            redeemer.key().as_ref(),
+            beneficiary.key().as_ref()
+        ],
+        bump,
+        space = 8 + RedemptionVesting::LEN,
+        payer = payer
+    )]
+    pub vesting: Account<'info, RedemptionVesting>,
+    /// Payer.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// System program.
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts for [redeemer::redeem_tokens_vested].
+#[derive(Accounts)]
+pub struct RedeemTokensVested<'info> {
+    /// Redeemer PDA.
+    pub redeemer: Account<'info, Redeemer>,
+    /// Tokens.
+    pub tokens: MutTokenPair<'info>,
+    /// Authority of the source of the redeemed tokens: its owner or delegate.
+    pub source_authority: Signer<'info>,
+    /// Source of the IOU tokens.
+    #[account(mut)]
+    pub iou_source: Box<Account<'info, TokenAccount>>,
+    /// The [RedemptionVesting] of the source authority.
+    #[account(mut)]
+    pub vesting: Account<'info, RedemptionVesting>,
+    /// Mint proxy config.
+    pub mint_proxy_config: Box<Account<'info, MintProxyConfig>>,
+    /// Minter information of the [Redeemer].
+    pub minter_info: Box<Account<'info, VersionedMinterInfo>>,
+}
+
+/// Accounts for [redeemer::withdraw_vested].
+#[derive(Accounts)]
+pub struct WithdrawVested<'info> {
+    /// Redeemer PDA.
+    pub redeemer: Box<Account<'info, Redeemer>>,
+    /// Beneficiary of the [RedemptionVesting].
+    pub beneficiary: Signer<'info>,
+    /// The [RedemptionVesting].
+    #[account(mut)]
+    pub vesting: Box<Account<'info, RedemptionVesting>>,
+    /// Mint of the redemption token.
+    #[account(mut)]
+    pub redemption_mint: Box<Account<'info, Mint>>,
+    /// Destination of the vested tokens.
+    #[account(mut)]
+    pub destination: Box<Account<'info, TokenAccount>>,
+    /// Mint proxy config.
+    pub mint_proxy_config: Box<Account<'info, MintProxyConfig>>,
+    /// Proxy mint authority.
+    /// Owned by the mint proxy.
+    /// CHECK: Arbitrary.
+    pub proxy_mint_authority: UncheckedAccount<'info>,
+    /// Mint proxy program.
+    pub mint_proxy_program: Program<'info, mint_proxy::program::MintProxy>,
+    /// Minter information.
+    #[account(mut)]
+    pub minter_info: Box<Account<'info, VersionedMinterInfo>>,
+    /// The spl_token program.
+    pub token_program: Program<'info, Token>,
+}
+
 /// Accounts for [redeemer::redeem_tokens].
 #[derive(Accounts)]
 pub struct RedeemTokens<'info> {
@@ -219,12 +1073,12 @@ pub struct RedeemTokens<'info> {
     pub redeemer: Account<'info, Redeemer>,
     /// Tokens.
     pub tokens: MutTokenPair<'info>,
-    /// Authority of the source of the redeemed tokens.
+    /// Authority of the source of the redeemed tokens: its owner or delegate.
     pub source_authority: Signer<'info>,
     /// Source of the IOU tokens.
     #[account(mut)]
     pub iou_source: Box<Account<'info, TokenAccount>>,
-    /// Destination of the IOU tokens.
+    /// Destination of the redemption tokens. May be owned by anyone.
     #[account(mut)]
     pub redemption_destination: Box<Account<'info, TokenAccount>>,
 }
@@ -245,25 +1099,177 @@ pub struct RedeemTokensFromMintProxy<'info> {
     pub mint_proxy_program: Program<'info, mint_proxy::program::MintProxy>,
     /// Minter information.
     #[account(mut)]
-    pub minter_info: Box<Account<'info, MinterInfo>>,
+    pub minter_info: Box<Account<'info, VersionedMinterInfo>>,
+}
+
+/// Accounts for [redeemer::redeem_tokens_from_mint_proxy_v2].
+#[derive(Accounts)]
+pub struct RedeemTokensFromMintProxyV2<'info> {
+    /// Redeem tokens.
+    pub redeem_ctx: RedeemTokens<'info>,
+    /// Mint proxy config.
+    pub mint_proxy_config: Box<Account<'info, MintProxyConfig>>,
+    /// Proxy mint authority.
+    /// Owned by the mint proxy.
+    /// CHECK: Arbitrary.
+    pub proxy_mint_authority: UncheckedAccount<'info>,
+    /// Mint proxy program.
+    pub mint_proxy_program: Program<'info, mint_proxy::program::MintProxy>,
+    /// Minter information.
+    #[account(mut)]
+    pub minter_info: Box<Account<'info, VersionedMinterInfo>>,
 }
 
 // --------------------------------
 // Events
 // --------------------------------
 
+/// Emitted when a [Redeemer] is created.
+#[event]
+pub struct CreateRedeemerEvent {
+    /// The [Redeemer].
+    #[index]
+    pub redeemer: Pubkey,
+    /// Mint of the IOU token.
+    pub iou_mint: Pubkey,
+    /// Mint of the redemption token.
+    pub redemption_mint: Pubkey,
+    /// Numerator of the exchange rate.
+    pub exchange_rate_numerator: u64,
+    /// Denominator of the exchange rate.
+    pub exchange_rate_denominator: u64,
+    /// The [Redeemer::admin].
+    pub admin: Pubkey,
+}
+
+/// Emitted when the [Redeemer::admin] is changed.
+#[event]
+pub struct SetAdminEvent {
+    /// The [Redeemer].
+    #[index]
+    pub redeemer: Pubkey,
+    /// The previous admin.
+    pub previous_admin: Pubkey,
+    /// The new admin.
+    pub admin: Pubkey,
+}
+
+/// Emitted when the redemption window is set.
+#[event]
+pub struct SetRedemptionWindowEvent {
+    /// The [Redeemer].
+    #[index]
+    pub redeemer: Pubkey,
+    /// Time from which tokens may be redeemed.
+    pub start_ts: i64,
+    /// Time from which tokens may no longer be redeemed.
+    pub end_ts: i64,
+}
+
+/// Emitted when redemptions are paused or unpaused.
+#[event]
+pub struct SetPausedEvent {
+    /// The [Redeemer].
+    #[index]
+    pub redeemer: Pubkey,
+    /// Whether redemptions are paused.
+    pub paused: bool,
+    /// Time from which tokens may no longer be redeemed, which unpausing may extend.
+    pub end_ts: i64,
+}
+
+/// Emitted when the vesting duration is set.
+#[event]
+pub struct SetVestingDurationEvent {
+    /// The [Redeemer].
+    #[index]
+    pub redeemer: Pubkey,
+    /// Duration over which redeemed tokens vest.
+    pub vesting_duration: i64,
+}
+
+/// Emitted when a user redeems tokens into a [RedemptionVesting].
+#[event]
+pub struct RedeemTokensVestedEvent {
+    /// User redeemed.
+    #[index]
+    pub user: Pubkey,
+    /// Mint of the IOU redeemed.
+    pub iou_mint: Pubkey,
+    /// Mint of the redemption token.
+    pub destination_mint: Pubkey,
+    /// Amount of IOU tokens redeemed.
+    pub amount: u64,
+    /// Amount of redemption tokens added to the vesting schedule.
+    pub redemption_amount: u64,
+    /// Beneficiary of the [RedemptionVesting].
+    pub beneficiary: Pubkey,
+    /// The time at which all tokens of the schedule have vested.
+    pub end_ts: i64,
+}
+
+/// Emitted when the order of hybrid redemptions is set.
+#[event]
+pub struct SetMintFirstEvent {
+    /// The [Redeemer].
+    #[index]
+    pub redeemer: Pubkey,
+    /// Whether hybrid redemptions mint before drawing from the redemption vault.
+    pub mint_first: bool,
+}
+
+/// Emitted alongside [RedeemTokensEvent] for hybrid redemptions.
+#[event]
+pub struct RedeemTokensHybridEvent {
+    /// User redeemed.
+    #[index]
+    pub user: Pubkey,
+    /// Amount of redemption tokens drawn from the redemption vault.
+    pub vault_amount: u64,
+    /// Amount of redemption tokens minted.
+    pub mint_amount: u64,
+}
+
+/// Emitted when vested tokens are withdrawn.
+#[event]
+pub struct WithdrawVestedEvent {
+    /// Beneficiary of the [RedemptionVesting].
+    #[index]
+    pub beneficiary: Pubkey,
+    /// The [Redeemer].
+    pub redeemer: Pubkey,
+    /// Amount of tokens withdrawn.
+    pub amount: u64,
+}
+
+/// Emitted when the redemption vault is swept.
+#[event]
+pub struct SweepEvent {
+    /// The [Redeemer].
+    #[index]
+    pub redeemer: Pubkey,
+    /// Token account which received the tokens.
+    pub treasury: Pubkey,
+    /// Amount of tokens swept.
+    pub amount: u64,
+}
+
 /// Emitted when a user redeems tokens.
 #[event]
 pub struct RedeemTokensEvent {
-    /// User redeemed.
+    /// Authority which redeemed: the owner or delegate of the IOU tokens.
     #[index]
     pub user: Pubkey,
     /// Mint of the IOU redeemed.
     pub iou_mint: Pubkey,
     /// Mint of the redemption token.
     pub destination_mint: Pubkey,
-    /// Amount redeemed.
+    /// Amount of IOU tokens redeemed.
     pub amount: u64,
+    /// Amount of redemption tokens received.
+    pub redemption_amount: u64,
+    /// Token account which received the redemption tokens.
+    pub recipient: Pubkey,
 }
 
 /// Errors.
@@ -273,4 +1279,238 @@ pub enum ErrorCode {
     Unauthorized,
     #[msg("Redemption token and IOU token decimals must match")]
     DecimalsMismatch,
+    #[msg("Exchange rate numerator and denominator must be non-zero.")]
+    InvalidExchangeRate,
+    #[msg("Amount of IOU tokens is too small to redeem anything.")]
+    RedemptionAmountTooSmall,
+    #[msg("Redemption window end must be zero or after its start.")]
+    InvalidRedemptionWindow,
+    #[msg("Redemptions are paused.")]
+    RedeemerPaused,
+    #[msg("Redemptions have not started.")]
+    RedemptionNotStarted,
+    #[msg("Redemptions have ended.")]
+    RedemptionEnded,
+    #[msg("Redemption window has not closed.")]
+    RedemptionWindowOpen,
+    #[msg("Vesting duration must not be negative.")]
+    InvalidVestingDuration,
+    #[msg("Redeemed tokens vest; use redeem_tokens_vested.")]
+    RedemptionVests,
+    #[msg("Redeemed tokens do not vest.")]
+    RedemptionDoesNotVest,
+    #[msg("Unknown router action.")]
+    UnknownAction,
+    #[msg("Minimum amount out not met.")]
+    MinimumOutNotMet,
+    #[msg("An open redemption window must stay open for the notice period.")]
+    RedemptionNoticeTooShort,
+    #[msg("Insufficient mint capacity for the redemption.")]
+    InsufficientMintCapacity,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn redeemer(numerator: u64, denominator: u64) -> Redeemer {
+        Redeemer {
+            exchange_rate_numerator: numerator,
+            exchange_rate_denominator: denominator,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_redemption_amount_one_to_one() {
+        let r = redeemer(1, 1);
+        assert_eq!(r.redemption_amount(0), Some(0));
+        assert_eq!(r.redemption_amount(1_000), Some(1_000));
+        assert_eq!(r.redemption_amount(u64::MAX), Some(u64::MAX));
+    }
+
+    #[test]
+    fn test_redemption_amount_rounds_down() {
+        // 1 old = 0.8 new
+        let r = redeemer(4, 5);
+        assert_eq!(r.redemption_amount(5), Some(4));
+        assert_eq!(r.redemption_amount(9), Some(7));
+        assert_eq!(r.redemption_amount(1), Some(0));
+        assert_eq!(r.checked_redemption_amount(0).unwrap(), 0);
+        assert!(r.checked_redemption_amount(1).is_err());
+        assert_eq!(r.checked_redemption_amount(2).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_redemption_amount_decimals() {
+        // 6 decimals IOU to 9 decimals redemption token
+        let r = redeemer(1_000, 1);
+        assert_eq!(r.redemption_amount(1_500_000), Some(1_500_000_000));
+        assert_eq!(r.redemption_amount(u64::MAX), None);
+
+        // 9 decimals IOU to 6 decimals redemption token
+        let r = redeemer(1, 1_000);
+        assert_eq!(r.redemption_amount(1_500_000_999), Some(1_500_000));
+        assert_eq!(r.redemption_amount(u64::MAX), Some(u64::MAX / 1_000));
+    }
+
+    #[test]
+    fn test_redemption_amount_unset() {
+        assert_eq!(redeemer(0, 0).redemption_amount(1), None);
+    }
+
+    #[test]
+    fn test_check_redeemable() {
+        let mut r = redeemer(1, 1);
+        assert!(r.check_redeemable(0).is_ok());
+        assert!(r.check_redeemable(i64::MAX).is_ok());
+
+        r.start_ts = i64::MIN;
+        assert!(r.check_redeemable(i64::MIN).is_ok());
+
+        r.start_ts = 100;
+        r.end_ts = 200;
+        assert!(r.check_redeemable(99).is_err());
+        assert!(r.check_redeemable(100).is_ok());
+        assert!(r.check_redeemable(199).is_ok());
+        assert!(r.check_redeemable(200).is_err());
+
+        r.paused = true;
+        assert!(r.check_redeemable(150).is_err());
+    }
+
+    #[test]
+    fn test_check_redemption_window() {
+        let mut r = redeemer(1, 1);
+        r.start_ts = 100;
+        r.end_ts = 0;
+        let notice_end = 1_000 + REDEMPTION_WINDOW_NOTICE;
+
+        // the open window may not be closed right away
+        assert!(r.check_redemption_window(100, 1_000, 1_000).is_err());
+        assert!(r
+            .check_redemption_window(100, notice_end - 1, 1_000)
+            .is_err());
+        assert!(r.check_redemption_window(100, notice_end, 1_000).is_ok());
+        assert!(r.check_redemption_window(100, 0, 1_000).is_ok());
+        // nor be closed by moving its start
+        assert!(r.check_redemption_window(1_001, 0, 1_000).is_err());
+        assert!(r.check_redemption_window(1_000, 0, 1_000).is_ok());
+
+        // a window ending before the notice period may be extended, not shortened
+        r.end_ts = 2_000;
+        assert!(r.check_redemption_window(100, 1_999, 1_000).is_err());
+        assert!(r.check_redemption_window(100, 2_000, 1_000).is_ok());
+        assert!(r.check_redemption_window(100, 3_000, 1_000).is_ok());
+
+        // windows which are not open may be changed freely
+        assert!(r.check_redemption_window(100, 2_500, 2_000).is_ok());
+        assert!(r.check_redemption_window(50, 60, 2_000).is_ok());
+        r.start_ts = 5_000;
+        r.end_ts = 6_000;
+        assert!(r.check_redemption_window(7_000, 8_000, 1_000).is_ok());
+        assert!(r.check_redemption_window(7_000, 7_000, 1_000).is_err());
+    }
+
+    #[test]
+    fn test_set_paused_keeps_notice() {
+        let mut r = redeemer(1, 1);
+        r.start_ts = 100;
+        r.end_ts = 0;
+
+        // the window is shortened to the notice period, then paused
+        r.check_redemption_window(100, 1_000 + REDEMPTION_WINDOW_NOTICE, 1_000)
+            .unwrap();
+        r.end_ts = 1_000 + REDEMPTION_WINDOW_NOTICE;
+        r.set_paused(true, 1_000).unwrap();
+        let now = 1_000 + 2 * REDEMPTION_WINDOW_NOTICE;
+        assert!(r.check_sweepable(now).is_err());
+
+        // unpausing restores the full notice
+        r.set_paused(false, now).unwrap();
+        assert_eq!(r.end_ts, now + REDEMPTION_WINDOW_NOTICE);
+        assert!(r.check_sweepable(now).is_err());
+        assert!(r.check_sweepable(now + REDEMPTION_WINDOW_NOTICE).is_ok());
+    }
+
+    #[test]
+    fn test_set_paused_partial_notice() {
+        let mut r = redeemer(1, 1);
+        r.start_ts = 100;
+        r.end_ts = 2_000;
+
+        // pausing again does not restart the pause
+        r.set_paused(true, 1_500).unwrap();
+        r.set_paused(true, 1_900).unwrap();
+        r.set_paused(false, 5_000).unwrap();
+        assert_eq!(r.end_ts, 5_500);
+
+        // a window far from its end is not extended
+        r.end_ts = 5_000 + 2 * REDEMPTION_WINDOW_NOTICE;
+        r.set_paused(true, 5_000).unwrap();
+        r.set_paused(false, 6_000).unwrap();
+        assert_eq!(r.end_ts, 5_000 + 2 * REDEMPTION_WINDOW_NOTICE);
+    }
+
+    #[test]
+    fn test_set_paused_outside_window() {
+        let mut r = redeemer(1, 1);
+        r.start_ts = 100;
+        r.end_ts = 2_000;
+
+        // pausing once the window has closed does not reopen it
+        r.set_paused(true, 2_000).unwrap();
+        assert!(r.check_sweepable(2_500).is_ok());
+        r.set_paused(false, 3_000).unwrap();
+        assert_eq!(r.end_ts, 2_000);
+        assert!(r.check_sweepable(3_000).is_ok());
+
+        // a pause ending before the window opens does not extend it,
+        // while one spanning its opening only counts the open time
+        r.start_ts = 4_000;
+        r.end_ts = 4_500;
+        r.set_paused(true, 3_000).unwrap();
+        r.set_paused(false, 3_500).unwrap();
+        assert_eq!(r.end_ts, 4_500);
+        r.set_paused(true, 3_500).unwrap();
+        r.set_paused(false, 5_000).unwrap();
+        assert_eq!(r.end_ts, 5_500);
+    }
+
+    #[test]
+    fn test_split_hybrid_redemption() {
+        let mut r = redeemer(1, 1);
+        assert_eq!(r.split_hybrid_redemption(100, 1_000, 1_000), (100, 0));
+        assert_eq!(r.split_hybrid_redemption(100, 30, 1_000), (30, 70));
+        assert_eq!(r.split_hybrid_redemption(100, 0, 0), (0, 100));
+
+        r.mint_first = true;
+        assert_eq!(r.split_hybrid_redemption(100, 1_000, 1_000), (0, 100));
+        assert_eq!(r.split_hybrid_redemption(100, 1_000, 30), (70, 30));
+        assert_eq!(r.split_hybrid_redemption(100, 0, 0), (100, 0));
+    }
+
+    #[test]
+    fn test_redemption_vesting() {
+        let mut vesting = RedemptionVesting::default();
+        vesting.top_up(1_000, 100, 100).unwrap();
+        assert_eq!(vesting.available_for_withdrawal(100), Some(0));
+        assert_eq!(vesting.available_for_withdrawal(150), Some(500));
+        assert_eq!(vesting.available_for_withdrawal(300), Some(1_000));
+
+        assert_eq!(vesting.withdraw(125), Some(250));
+        assert_eq!(vesting.available_for_withdrawal(150), Some(250));
+
+        // 500 vested (250 unwithdrawn), 500 unvested
+        vesting.top_up(500, 150, 100).unwrap();
+        assert_eq!(vesting.claimable, 250);
+        assert_eq!(vesting.start_balance, 1_000);
+        assert_eq!(vesting.available_for_withdrawal(150), Some(250));
+        assert_eq!(vesting.available_for_withdrawal(200), Some(750));
+
+        assert_eq!(vesting.withdraw(200), Some(750));
+        assert_eq!(vesting.withdraw(200), Some(0));
+        assert_eq!(vesting.withdraw(250), Some(500));
+        assert_eq!(vesting.available_for_withdrawal(1_000), Some(0));
+    }
 }
@@ -0,0 +1,43 @@
+use crate::*;
+
+use vipers::validate::Validate;
+use vipers::{assert_keys_eq, invariant};
+
+impl<'info> Validate<'info> for CreateTimelock<'info> {
+    fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> Validate<'info> for QueueAction<'info> {
+    fn validate(&self) -> Result<()> {
+        require!(self.admin.is_signer, Unauthorized);
+        assert_keys_eq!(self.admin, self.timelock.admin, Unauthorized);
+        Ok(())
+    }
+}
+
+impl<'info> Validate<'info> for CancelAction<'info> {
+    fn validate(&self) -> Result<()> {
+        require!(self.admin.is_signer, Unauthorized);
+        assert_keys_eq!(self.admin, self.timelock.admin, Unauthorized);
+        assert_keys_eq!(self.action.timelock, self.timelock, "action.timelock");
+        invariant!(self.action.executed_at == -1, ActionAlreadyExecuted);
+        invariant!(self.action.canceled_at == -1, ActionCanceled);
+        Ok(())
+    }
+}
+
+impl<'info> Validate<'info> for ExecuteAction<'info> {
+    fn validate(&self) -> Result<()> {
+        assert_keys_eq!(self.action.timelock, self.timelock, "action.timelock");
+        Ok(())
+    }
+}
+
+impl<'info> Validate<'info> for TimelockAuth<'info> {
+    fn validate(&self) -> Result<()> {
+        require!(self.timelock.to_account_info().is_signer, Unauthorized);
+        Ok(())
+    }
+}
@@ -0,0 +1,517 @@
+//! Delays admin actions of the Saber periphery programs.
+//!
+//! A [Timelock] is a PDA which may be made the owner of another program's
+//! admin state. Instructions signed by the [Timelock] must first be queued
+//! by its admin and may only be executed once their ETA has passed.
+#![deny(rustdoc::all)]
+#![allow(rustdoc::missing_doc_code_examples)]
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    instruction::Instruction, program::invoke_signed, pubkey::PUBKEY_BYTES,
+};
+use vipers::prelude::*;
+
+mod account_validators;
+mod macros;
+
+declare_id!("HyWPfsCBzd6E7mvugNz3o97PX2im93stbtBsiqwZdiS8");
+
+/// Number of seconds after its ETA during which a [QueuedAction] may be
+/// executed. Actions which are not executed in time expire and must be
+/// queued again.
+pub const GRACE_PERIOD: i64 = 14 * 24 * 60 * 60;
+
+#[program]
+/// [timelock] program.
+pub mod timelock {
+    use super::*;
+
+    /// Creates a [Timelock].
+    #[access_control(ctx.accounts.validate())]
+    pub fn create_timelock(ctx: Context<CreateTimelock>, admin: Pubkey, delay: i64) -> Result<()> {
+        require!(delay >= 0, InvalidDelay);
+
+        let timelock = &mut ctx.accounts.timelock;
+        timelock.base = ctx.accounts.base.key();
+        timelock.bump = unwrap_bump!(ctx, "timelock");
+        timelock.admin = admin;
+        timelock.delay = delay;
+        timelock.num_actions = 0;
+
+        emit!(TimelockCreatedEvent {
+            timelock: timelock.key(),
+            admin,
+            delay,
+        });
+
+        Ok(())
+    }
+
+    /// Queues an instruction to be signed by the [Timelock] at or after `eta`.
+    #[access_control(ctx.accounts.validate())]
+    pub fn queue_action(ctx: Context<QueueAction>, ix: TXInstruction, eta: i64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let earliest_eta = unwrap_int!(now.checked_add(ctx.accounts.timelock.delay));
+        require!(eta >= earliest_eta, ETATooEarly);
+
+        let timelock = &mut ctx.accounts.timelock;
+        let action = &mut ctx.accounts.action;
+        action.timelock = timelock.key();
+        action.index = timelock.num_actions;
+        action.bump = unwrap_bump!(ctx, "action");
+        action.queued_at = now;
+        action.eta = eta;
+        action.executed_at = -1;
+        action.canceled_at = -1;
+        action.instruction = ix;
+
+        timelock.num_actions = unwrap_int!(timelock.num_actions.checked_add(1));
+
+        emit!(ActionQueuedEvent {
+            timelock: action.timelock,
+            index: action.index,
+            program_id: action.instruction.program_id,
+            eta,
+        });
+
+        Ok(())
+    }
+
+    /// Cancels a queued action.
+    #[access_control(ctx.accounts.validate())]
+    pub fn cancel_action(ctx: Context<CancelAction>) -> Result<()> {
+        let action = &mut ctx.accounts.action;
+        action.canceled_at = Clock::get()?.unix_timestamp;
+
+        emit!(ActionCanceledEvent {
+            timelock: action.timelock,
+            index: action.index,
+        });
+
+        Ok(())
+    }
+
+    /// Executes a queued action whose ETA has passed, at most [GRACE_PERIOD]
+    /// seconds after it. Anyone may call this.
+    ///
+    /// The remaining accounts must contain every account of the queued
+    /// instruction, as well as the program being invoked.
+    #[access_control(ctx.accounts.validate())]
+    pub fn execute_action<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteAction<'info>>,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        ctx.accounts.action.check_executable(now)?;
+
+        let action = &mut ctx.accounts.action;
+        action.executed_at = now;
+        // Persist before invoking so that the action cannot be re-entered.
+        action.exit(ctx.program_id)?;
+
+        let timelock = &ctx.accounts.timelock;
+        let seeds = gen_timelock_signer_seeds!(timelock);
+        let ix: Instruction = (&action.instruction).into();
+        invoke_signed(&ix, ctx.remaining_accounts, &[&seeds[..]])?;
+
+        emit!(ActionExecutedEvent {
+            timelock: action.timelock,
+            index: action.index,
+        });
+
+        Ok(())
+    }
+
+    /// Sets the delay. Must be queued through the [Timelock] itself.
+    #[access_control(ctx.accounts.validate())]
+    pub fn set_delay(ctx: Context<TimelockAuth>, delay: i64) -> Result<()> {
+        require!(delay >= 0, InvalidDelay);
+        let timelock = &mut ctx.accounts.timelock;
+        timelock.delay = delay;
+
+        emit!(DelaySetEvent {
+            timelock: timelock.key(),
+            delay,
+        });
+
+        Ok(())
+    }
+
+    /// Sets the admin. Must be queued through the [Timelock] itself.
+    #[access_control(ctx.accounts.validate())]
+    pub fn set_admin(ctx: Context<TimelockAuth>, admin: Pubkey) -> Result<()> {
+        let timelock = &mut ctx.accounts.timelock;
+        timelock.admin = admin;
+
+        emit!(AdminSetEvent {
+            timelock: timelock.key(),
+            admin,
+        });
+
+        Ok(())
+    }
+}
+
+// --------------------------------
+// Accounts
+// --------------------------------
+
+/// Signs queued instructions once their ETA has passed.
+#[account]
+#[derive(Default, Debug)]
+pub struct Timelock {
+    /// Base key used to derive the [Timelock] PDA.
+    pub base: Pubkey,
+    /// Bump seed.
+    pub bump: u8,
+    /// Account which may queue and cancel actions.
+    pub admin: Pubkey,
+    /// Minimum number of seconds between queueing and executing an action.
+    pub delay: i64,
+    /// Total number of actions queued. Used to derive [QueuedAction] PDAs.
+    pub num_actions: u64,
+}
+
+impl Timelock {
+    pub const LEN: usize = PUBKEY_BYTES + 1 + PUBKEY_BYTES + 8 + 8;
+}
+
+/// An instruction queued on a [Timelock].
+#[account]
+#[derive(Default, Debug)]
+pub struct QueuedAction {
+    /// The [Timelock].
+    pub timelock: Pubkey,
+    /// Index of the action within the [Timelock].
+    pub index: u64,
+    /// Bump seed.
+    pub bump: u8,
+    /// When the action was queued.
+    pub queued_at: i64,
+    /// Earliest time at which the action may be executed.
+    pub eta: i64,
+    /// When the action was executed, or -1 if it has not been.
+    pub executed_at: i64,
+    /// When the action was canceled, or -1 if it has not been.
+    pub canceled_at: i64,
+    /// The instruction to invoke.
+    pub instruction: TXInstruction,
+}
+
+impl QueuedAction {
+    /// Number of bytes required to store a [QueuedAction] for an instruction.
+    pub fn space(ix: &TXInstruction) -> usize {
+        PUBKEY_BYTES + 8 + 1 + 8 * 4 + ix.space()
+    }
+
+    /// Checks that the action has neither been executed nor canceled and
+    /// that `now` is within [GRACE_PERIOD] seconds after its ETA.
+    pub fn check_executable(&self, now: i64) -> Result<()> {
+        require!(self.executed_at == -1, ActionAlreadyExecuted);
+        require!(self.canceled_at == -1, ActionCanceled);
+        require!(now >= self.eta, ETANotReached);
+        require!(now <= self.eta.saturating_add(GRACE_PERIOD), ActionExpired);
+        Ok(())
+    }
+}
+
+/// Serializable version of [Instruction].
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct TXInstruction {
+    /// Program to invoke.
+    pub program_id: Pubkey,
+    /// Accounts passed to the program.
+    pub keys: Vec<TXAccountMeta>,
+    /// Instruction data.
+    pub data: Vec<u8>,
+}
+
+impl TXInstruction {
+    /// Number of bytes required to serialize the instruction.
+    pub fn space(&self) -> usize {
+        PUBKEY_BYTES + 4 + self.keys.len() * TXAccountMeta::LEN + 4 + self.data.len()
+    }
+}
+
+/// Serializable version of [AccountMeta].
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TXAccountMeta {
+    /// Account address.
+    pub pubkey: Pubkey,
+    /// True if the instruction requires a signature for this account.
+    pub is_signer: bool,
+    /// True if the account is writable.
+    pub is_writable: bool,
+}
+
+impl TXAccountMeta {
+    pub const LEN: usize = PUBKEY_BYTES + 1 + 1;
+}
+
+impl From<&TXInstruction> for Instruction {
+    fn from(ix: &TXInstruction) -> Instruction {
+        Instruction {
+            program_id: ix.program_id,
+            accounts: ix.keys.iter().map(|meta| meta.into()).collect(),
+            data: ix.data.clone(),
+        }
+    }
+}
+
+impl From<&TXAccountMeta> for AccountMeta {
+    fn from(meta: &TXAccountMeta) -> AccountMeta {
+        AccountMeta {
+            pubkey: meta.pubkey,
+            is_signer: meta.is_signer,
+            is_writable: meta.is_writable,
+        }
+    }
+}
+
+impl From<Instruction> for TXInstruction {
+    fn from(ix: Instruction) -> TXInstruction {
+        TXInstruction {
+            program_id: ix.program_id,
+            keys: ix
+                .accounts
+                .into_iter()
+                .map(|meta| TXAccountMeta {
+                    pubkey: meta.pubkey,
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect(),
+            data: ix.data,
+        }
+    }
+}
+
+// --------------------------------
+// Instructions
+// --------------------------------
+
+/// Accounts for [timelock::create_timelock].
+#[derive(Accounts)]
+pub struct CreateTimelock<'info> {
+    /// Base key of the [Timelock].
+    pub base: Signer<'info>,
+    /// [Timelock] PDA.
+    #[account(
+        init,
+        seeds = [
+            b"Timelock".as_ref(),
+            base.key().as_ref()
+        ],
+        bump,
+        space = 8 + Timelock::LEN,
+        payer = payer
+    )]
+    pub timelock: Account<'info, Timelock>,
+    /// Payer.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// System program.
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts for [timelock::queue_action].
+#[derive(Accounts)]
+#[instruction(ix: TXInstruction)]
+pub struct QueueAction<'info> {
+    /// The [Timelock].
+    #[account(mut)]
+    pub timelock: Account<'info, Timelock>,
+    /// The [QueuedAction] PDA.
+    #[account(
+        init,
+        seeds = [
+            b"QueuedAction".as_ref(),
+            timelock.key().as_ref(),
+            timelock.num_actions.to_le_bytes().as_ref()
+        ],
+        bump,
+        space = 8 + QueuedAction::space(&ix),
+        payer = payer
+    )]
+    pub action: Account<'info, QueuedAction>,
+    /// Admin of the [Timelock].
+    pub admin: Signer<'info>,
+    /// Payer.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// System program.
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts for [timelock::cancel_action].
+#[derive(Accounts)]
+pub struct CancelAction<'info> {
+    /// The [Timelock].
+    pub timelock: Account<'info, Timelock>,
+    /// The [QueuedAction] to cancel.
+    #[account(mut)]
+    pub action: Account<'info, QueuedAction>,
+    /// Admin of the [Timelock].
+    pub admin: Signer<'info>,
+}
+
+/// Accounts for [timelock::execute_action].
+#[derive(Accounts)]
+pub struct ExecuteAction<'info> {
+    /// The [Timelock]. Not deserialized as mutable, as the executed
+    /// instruction may itself modify the [Timelock].
+    pub timelock: Account<'info, Timelock>,
+    /// The [QueuedAction] to execute.
+    #[account(mut)]
+    pub action: Account<'info, QueuedAction>,
+}
+
+/// Accounts for instructions which must be signed by the [Timelock] itself.
+#[derive(Accounts)]
+pub struct TimelockAuth<'info> {
+    /// The [Timelock].
+    #[account(mut, signer)]
+    pub timelock: Account<'info, Timelock>,
+}
+
+// --------------------------------
+// Events
+// --------------------------------
+
+/// Emitted when a [Timelock] is created.
+#[event]
+pub struct TimelockCreatedEvent {
+    /// The [Timelock].
+    #[index]
+    pub timelock: Pubkey,
+    /// Admin of the [Timelock].
+    pub admin: Pubkey,
+    /// Delay of the [Timelock].
+    pub delay: i64,
+}
+
+/// Emitted when an action is queued.
+#[event]
+pub struct ActionQueuedEvent {
+    /// The [Timelock].
+    #[index]
+    pub timelock: Pubkey,
+    /// Index of the [QueuedAction].
+    pub index: u64,
+    /// Program the action invokes.
+    pub program_id: Pubkey,
+    /// Earliest time at which the action may be executed.
+    pub eta: i64,
+}
+
+/// Emitted when an action is canceled.
+#[event]
+pub struct ActionCanceledEvent {
+    /// The [Timelock].
+    #[index]
+    pub timelock: Pubkey,
+    /// Index of the [QueuedAction].
+    pub index: u64,
+}
+
+/// Emitted when an action is executed.
+#[event]
+pub struct ActionExecutedEvent {
+    /// The [Timelock].
+    #[index]
+    pub timelock: Pubkey,
+    /// Index of the [QueuedAction].
+    pub index: u64,
+}
+
+/// Emitted when the delay of a [Timelock] changes.
+#[event]
+pub struct DelaySetEvent {
+    /// The [Timelock].
+    #[index]
+    pub timelock: Pubkey,
+    /// New delay.
+    pub delay: i64,
+}
+
+/// Emitted when the admin of a [Timelock] changes.
+#[event]
+pub struct AdminSetEvent {
+    /// The [Timelock].
+    #[index]
+    pub timelock: Pubkey,
+    /// New admin.
+    pub admin: Pubkey,
+}
+
+/// Errors.
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Unauthorized.")]
+    Unauthorized,
+    #[msg("Delay must not be negative.")]
+    InvalidDelay,
+    #[msg("ETA must be at least the delay after the current time.")]
+    ETATooEarly,
+    #[msg("Action ETA has not been reached.")]
+    ETANotReached,
+    #[msg("Action has already been executed.")]
+    ActionAlreadyExecuted,
+    #[msg("Action has been canceled.")]
+    ActionCanceled,
+    #[msg("Action grace period has elapsed.")]
+    ActionExpired,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_error(result: Result<()>, code: ErrorCode) {
+        assert!(matches!(
+            result.unwrap_err(),
+            anchor_lang::error::Error::AnchorError(err)
+                if err.error_code_number == u32::from(code)
+        ));
+    }
+
+    fn action(eta: i64) -> QueuedAction {
+        QueuedAction {
+            eta,
+            executed_at: -1,
+            canceled_at: -1,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_check_executable() {
+        let action = action(1_000);
+        assert_error(action.check_executable(999), ErrorCode::ETANotReached);
+        assert!(action.check_executable(1_000).is_ok());
+        assert!(action.check_executable(1_000 + GRACE_PERIOD).is_ok());
+        assert_error(
+            action.check_executable(1_001 + GRACE_PERIOD),
+            ErrorCode::ActionExpired,
+        );
+    }
+
+    #[test]
+    fn test_check_executable_once() {
+        let mut executed = action(1_000);
+        executed.executed_at = 1_000;
+        assert_error(
+            executed.check_executable(1_001),
+            ErrorCode::ActionAlreadyExecuted,
+        );
+
+        let mut canceled = action(1_000);
+        canceled.canceled_at = 500;
+        assert_error(canceled.check_executable(1_001), ErrorCode::ActionCanceled);
+    }
+
+    #[test]
+    fn test_check_executable_far_eta() {
+        assert!(action(i64::MAX).check_executable(i64::MAX).is_ok());
+    }
+}
@@ -0,0 +1,13 @@
+//! Macros.
+
+/// Generates the signer seeds for the [crate::Timelock].
+#[macro_export]
+macro_rules! gen_timelock_signer_seeds {
+    ($timelock:expr) => {
+        &[
+            b"Timelock" as &[u8],
+            &$timelock.base.to_bytes(),
+            &[$timelock.bump],
+        ]
+    };
+}